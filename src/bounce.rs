@@ -0,0 +1,265 @@
+use crate::gravity::quadratic_roots;
+use crate::{Float, Gravity, Resume, Simulation, Tolerance};
+
+/// a position under gravity which bounces off a floor, losing some of its speed with each bounce, like a
+/// dropped ball.
+///
+/// The position falls as with `Gravity` until it hits the floor, and then rebounds with its speed multiplied
+/// by the coefficient of restitution (0.0 doesn't bounce at all, and 1.0 bounces forever). Every bounce is
+/// solved directly, and once a rebound would be slower than the velocity tolerance the position comes to rest
+/// on the floor. This is nice for dropping toasts and dialogs into place.
+///
+/// Gravity pulls towards the floor, so the position should start on the other side of the floor from the
+/// direction of the acceleration (above the floor for a positive acceleration, where positions increase
+/// downwards).
+#[derive(Clone, Copy)]
+pub struct Bounce<F = f32> {
+    gravity: Gravity<F>,
+    floor: F,
+    restitution: F,
+    tolerance: Tolerance<F>,
+    impact_time: F, // of the first impact.
+    impact_speed: F,
+    bounces: usize,
+    rest_time: F,
+}
+impl<F: Float> Bounce<F> {
+    /// Create a new bounce with the given acceleration (as with `Gravity`), floor position and coefficient of
+    /// restitution.
+    ///
+    /// The position comes to rest once it would rebound at less than 1.0 per second, which suits values in
+    /// pixels. Use `set_tolerance` for values in other units.
+    pub fn new(a: F, floor: F, restitution: F) -> Bounce<F> {
+        let mut bounce = Bounce {
+            gravity: Gravity::with_acceleration(a),
+            floor,
+            restitution,
+            tolerance: Tolerance {
+                velocity: F::ONE,
+                ..Tolerance::default()
+            },
+            impact_time: F::NAN,
+            impact_speed: F::ZERO,
+            bounces: 0,
+            rest_time: F::NAN,
+        };
+        bounce.update();
+        bounce
+    }
+    /// Set the initial (time = 0.0) position and velocity.
+    pub fn set(&mut self, x: F, v: F) {
+        self.gravity.set(x, v);
+        self.update();
+    }
+    /// Set the position of the floor.
+    pub fn set_floor(&mut self, floor: F) {
+        self.floor = floor;
+        self.update();
+    }
+    /// Set the coefficient of restitution, which is the fraction of its speed that the position keeps
+    /// each time it bounces.
+    pub fn set_restitution(&mut self, restitution: F) {
+        self.restitution = restitution;
+        self.update();
+    }
+    /// Set the tolerance used to decide when the position comes to rest. Only the velocity is used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+        self.update();
+    }
+    /// Return the position of the floor.
+    pub fn floor(&self) -> F {
+        self.floor
+    }
+    /// Return the coefficient of restitution.
+    pub fn restitution(&self) -> F {
+        self.restitution
+    }
+    /// Return how many times the position bounces before coming to rest, or `usize::MAX` if it bounces
+    /// forever.
+    pub fn bounces(&self) -> usize {
+        self.bounces
+    }
+    /// Return the time (in seconds) at which the position hits the floor for the given impact (counting from
+    /// zero), or NaN if it never does. The impact after the last bounce is when the position comes to rest.
+    pub fn impact_time(&self, index: usize) -> F {
+        if !self.impact_time.is_finite() || index > self.bounces {
+            return F::NAN;
+        }
+        let restitution = self.restitution;
+        let flight = F::from_f64(2.0) * self.impact_speed / self.gravity.acceleration().abs();
+        let index = F::from_f64(index as f64);
+        if restitution == F::ONE {
+            self.impact_time + flight * index
+        } else {
+            // Each flight is shorter than the one before by the coefficient of restitution, so the flights
+            // add up to a geometric series.
+            self.impact_time
+                + flight * restitution * (F::ONE - restitution.powf(index)) / (F::ONE - restitution)
+        }
+    }
+    /// Return the velocity at which the position leaves the floor after the given impact (counting from
+    /// zero), which is zero for the impact at which it comes to rest, or NaN if there is no such impact.
+    pub fn rebound_velocity(&self, index: usize) -> F {
+        if !self.impact_time.is_finite() || index > self.bounces {
+            F::NAN
+        } else if index == self.bounces {
+            F::ZERO
+        } else {
+            let speed = self.impact_speed * self.restitution.powf(F::from_f64((index + 1) as f64));
+            if self.gravity.acceleration() > F::ZERO {
+                -speed
+            } else {
+                speed
+            }
+        }
+    }
+    /// Return the first time (in seconds, from zero) at which the position reaches `p`, or NaN if it never
+    /// does.
+    pub fn time_for_position(&self, p: F) -> F {
+        let fall = self.gravity.time_for_position(p);
+        if !self.impact_time.is_finite() || fall <= self.impact_time {
+            return fall;
+        }
+        // Look for the first bounce that reaches the position. Each bounce is lower than the one before
+        // (unless the restitution is one or more), so give up once one doesn't reach it.
+        let a = self.gravity.acceleration();
+        for index in 0..self.bounces {
+            let v = self.rebound_velocity(index);
+            // The bounce goes v * v / 2a from the floor, away from the direction of the acceleration.
+            let height = -v * v / (F::from_f64(2.0) * a);
+            let reach = (p - self.floor) / height;
+            if reach.is_nan() || reach < F::ZERO || (reach > F::ONE && self.restitution <= F::ONE) {
+                break;
+            }
+            if reach <= F::ONE {
+                let (first, _) = quadratic_roots(F::from_f64(0.5) * a, v, self.floor - p);
+                return self.impact_time(index) + first.max(F::ZERO);
+            }
+        }
+        F::NAN
+    }
+    /// Find the first impact, and how many bounces follow it.
+    fn update(&mut self) {
+        let a = self.gravity.acceleration();
+        let (x, v) = (self.gravity.x(F::ZERO), self.gravity.dx(F::ZERO));
+        // The position hits the floor moving in the direction of the acceleration, which is at the later of
+        // the two times that it passes the floor.
+        let (_, second) = quadratic_roots(F::from_f64(0.5) * a, v, x - self.floor);
+        if a == F::ZERO || second.is_nan() || second < F::ZERO {
+            self.impact_time = F::NAN;
+            self.impact_speed = F::ZERO;
+            self.bounces = 0;
+            self.rest_time = F::NAN;
+            return;
+        }
+        self.impact_time = second;
+        self.impact_speed = self.gravity.dx(second).abs();
+        // Bounce k rebounds at impact_speed * restitution^(k + 1), so count the bounces until that is below
+        // the tolerance.
+        let count = (self.tolerance.velocity / self.impact_speed).ln() / self.restitution.ln();
+        self.bounces = if self.restitution >= F::ONE && self.impact_speed > F::ZERO {
+            usize::MAX
+        } else if count > F::ZERO {
+            // Rounds down (and saturates).
+            count.to_f64() as usize
+        } else {
+            0
+        };
+        self.rest_time = if self.bounces == usize::MAX {
+            F::NAN
+        } else {
+            self.impact_time(self.bounces)
+        };
+    }
+    /// Return which bounce the position is in at the given time (after the first impact and before coming
+    /// to rest), and the time since that bounce started.
+    fn bounce_at(&self, time: F) -> (usize, F) {
+        let restitution = self.restitution;
+        let flight = F::from_f64(2.0) * self.impact_speed / self.gravity.acceleration().abs();
+        let elapsed = time - self.impact_time;
+        let estimate = if restitution == F::ONE {
+            elapsed / flight
+        } else {
+            // Invert the sum of the flights in `impact_time`.
+            (F::ONE - elapsed * (F::ONE - restitution) / (flight * restitution)).ln()
+                / restitution.ln()
+        };
+        let mut index = if estimate > F::ZERO {
+            (estimate.to_f64() as usize).min(self.bounces)
+        } else {
+            0
+        };
+        // Correct for rounding in the estimate.
+        while index > 0 && time < self.impact_time(index) {
+            index -= 1;
+        }
+        while index + 1 < self.bounces && time >= self.impact_time(index + 1) {
+            index += 1;
+        }
+        (index, time - self.impact_time(index))
+    }
+    fn bouncing(&self, time: F) -> bool {
+        self.impact_time.is_finite() && time >= self.impact_time
+    }
+}
+impl<F: Float> Simulation<F> for Bounce<F> {
+    fn x(&self, time: F) -> F {
+        if !self.bouncing(time) {
+            self.gravity.x(time)
+        } else if self.is_done(time) {
+            self.floor
+        } else {
+            let (index, local) = self.bounce_at(time);
+            let a = self.gravity.acceleration();
+            self.floor + self.rebound_velocity(index) * local + F::from_f64(0.5) * a * local * local
+        }
+    }
+    fn dx(&self, time: F) -> F {
+        if !self.bouncing(time) {
+            self.gravity.dx(time)
+        } else if self.is_done(time) {
+            F::ZERO
+        } else {
+            let (index, local) = self.bounce_at(time);
+            self.rebound_velocity(index) + self.gravity.acceleration() * local
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        if self.is_done(time) {
+            F::ZERO
+        } else {
+            self.gravity.acceleration()
+        }
+    }
+    fn is_done(&self, time: F) -> bool {
+        if self.impact_time.is_finite() {
+            time >= self.rest_time
+        } else {
+            self.gravity.is_done(time)
+        }
+    }
+    fn settle_time(&self) -> F {
+        if self.impact_time.is_finite() {
+            self.rest_time
+        } else {
+            self.gravity.settle_time()
+        }
+    }
+    fn final_position(&self) -> F {
+        if self.impact_time.is_finite() {
+            if self.rest_time.is_finite() {
+                self.floor
+            } else {
+                F::NAN
+            }
+        } else {
+            self.gravity.final_position()
+        }
+    }
+}
+impl<F: Float> Resume<F> for Bounce<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
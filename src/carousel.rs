@@ -0,0 +1,152 @@
+use crate::search::first_time_monotonic;
+use crate::{Float, Resume, Simulation, Tolerance};
+
+/// a position with velocity that slows down due to drag, but never below a cruising speed.
+///
+/// This is friction for infinite carousels, like this one:
+/// <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/iTunesRadio/">Gravitas JavaScript Friction Example</a>.
+/// A fling decays back to the cruising speed (speeding up again if it was slower), carrying on in whichever
+/// direction it was flung. Since `Resume` starts the carousel again, it goes back to cruising by itself once a
+/// gesture (or a `Handoff`) gives it back its position and velocity.
+///
+/// While the cruising speed isn't zero the carousel never stops, so it's never done and its settle time and
+/// final position are NaN. Use `cruise_time` to find out when it has settled into cruising.
+#[derive(Clone, Copy)]
+pub struct Carousel<F = f32> {
+    x: F,
+    v: F,
+    cruise: F,
+    cruise_velocity: F, // the cruising speed, in the direction of travel.
+    drag: F,
+    ln_drag: F,
+    tolerance: Tolerance<F>,
+}
+impl<F: Float> Carousel<F> {
+    /// Create a new carousel with the given drag value (as with `Friction`) and cruising speed. It cruises in
+    /// the direction of increasing position until it's set with a negative velocity.
+    ///
+    /// The carousel has settled into cruising once its velocity is within 1.0 of the cruising speed, which
+    /// suits values in pixels. Use `set_tolerance` for values in other units.
+    pub fn new(drag: F, cruise: F) -> Carousel<F> {
+        let cruise = cruise.abs();
+        Carousel {
+            x: F::ZERO,
+            v: cruise,
+            cruise,
+            cruise_velocity: cruise,
+            drag,
+            ln_drag: drag.ln(),
+            tolerance: Tolerance {
+                velocity: F::ONE,
+                ..Tolerance::default()
+            },
+        }
+    }
+    /// Set the initial (time = 0.0) position and velocity. The carousel cruises in the direction of the
+    /// velocity, or in the same direction as before if the velocity is zero.
+    pub fn set(&mut self, x: F, v: F) {
+        self.x = x;
+        self.v = v;
+        if v < F::ZERO {
+            self.cruise_velocity = -self.cruise;
+        } else if v > F::ZERO {
+            self.cruise_velocity = self.cruise;
+        }
+    }
+    /// Set the cruising speed. This applies from the next `set`.
+    pub fn set_cruise(&mut self, cruise: F) {
+        self.cruise = cruise.abs();
+        self.cruise_velocity = if self.cruise_velocity < F::ZERO {
+            -self.cruise
+        } else {
+            self.cruise
+        };
+    }
+    /// Return the cruising speed.
+    pub fn cruise(&self) -> F {
+        self.cruise
+    }
+    /// Return the velocity the carousel is heading towards, which is the cruising speed in the direction of
+    /// travel.
+    pub fn cruise_velocity(&self) -> F {
+        self.cruise_velocity
+    }
+    /// Return the drag value.
+    pub fn drag(&self) -> F {
+        self.drag
+    }
+    /// Set the tolerance used to decide when the carousel has settled. Only the velocity and time are used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+    }
+    /// Return the time (in seconds) at which the velocity comes within the tolerance of the cruising
+    /// velocity.
+    pub fn cruise_time(&self) -> F {
+        // Solve (v - cruise) * drag^t = tolerance for t.
+        let excess = (self.v - self.cruise_velocity).abs();
+        if excess < self.tolerance.velocity {
+            F::ZERO
+        } else {
+            (self.tolerance.velocity / excess).ln() / self.ln_drag
+        }
+    }
+    /// Return the time (in seconds) at which the carousel reaches position `p`, or NaN if it never does
+    /// (including positions behind where it started).
+    pub fn time_for_position(&self, p: F) -> F {
+        let distance = p - self.x;
+        if distance == F::ZERO {
+            return F::ZERO;
+        }
+        // The carousel heads towards its cruising velocity, or stops if it doesn't cruise.
+        let heading = if self.cruise == F::ZERO {
+            self.v
+        } else {
+            self.cruise_velocity
+        };
+        if heading == F::ZERO || distance / heading < F::ZERO {
+            return F::NAN;
+        }
+        if self.cruise == F::ZERO && (self.final_position() - p) / heading < F::ZERO {
+            return F::NAN;
+        }
+        // The position only moves one way, so it stays past the position once it gets there.
+        first_time_monotonic(F::ZERO, self.tolerance.time, |t| {
+            (self.x(t) - p) / heading >= F::ZERO
+        })
+    }
+}
+impl<F: Float> Simulation<F> for Carousel<F> {
+    fn x(&self, time: F) -> F {
+        self.x
+            + self.cruise_velocity * time
+            + (self.v - self.cruise_velocity) * (self.drag.powf(time) - F::ONE) / self.ln_drag
+    }
+    fn dx(&self, time: F) -> F {
+        self.cruise_velocity + (self.v - self.cruise_velocity) * self.drag.powf(time)
+    }
+    fn ddx(&self, time: F) -> F {
+        (self.v - self.cruise_velocity) * self.ln_drag * self.drag.powf(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.cruise == F::ZERO && self.dx(time).abs() < self.tolerance.velocity
+    }
+    fn settle_time(&self) -> F {
+        if self.cruise == F::ZERO {
+            self.cruise_time()
+        } else {
+            F::NAN
+        }
+    }
+    fn final_position(&self) -> F {
+        if self.cruise == F::ZERO {
+            self.x - self.v / self.ln_drag
+        } else {
+            F::NAN
+        }
+    }
+}
+impl<F: Float> Resume<F> for Carousel<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{Float, Simulation};
+
+/// a simulation with a constant added to its position, created by `Simulation::offset`.
+#[derive(Clone, Copy)]
+pub struct Offset<S, F = f32> {
+    simulation: S,
+    offset: F,
+}
+impl<S, F> Offset<S, F> {
+    pub(crate) fn new(simulation: S, offset: F) -> Offset<S, F> {
+        Offset { simulation, offset }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for Offset<S, F> {
+    fn x(&self, time: F) -> F {
+        self.simulation.x(time) + self.offset
+    }
+    fn dx(&self, time: F) -> F {
+        self.simulation.dx(time)
+    }
+    fn ddx(&self, time: F) -> F {
+        self.simulation.ddx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.simulation.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.simulation.final_position() + self.offset
+    }
+}
+
+/// a simulation with its position multiplied by a constant, created by `Simulation::scale`.
+#[derive(Clone, Copy)]
+pub struct Scale<S, F = f32> {
+    simulation: S,
+    scale: F,
+}
+impl<S, F> Scale<S, F> {
+    pub(crate) fn new(simulation: S, scale: F) -> Scale<S, F> {
+        Scale { simulation, scale }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for Scale<S, F> {
+    fn x(&self, time: F) -> F {
+        self.simulation.x(time) * self.scale
+    }
+    fn dx(&self, time: F) -> F {
+        self.simulation.dx(time) * self.scale
+    }
+    fn ddx(&self, time: F) -> F {
+        self.simulation.ddx(time) * self.scale
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.simulation.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.simulation.final_position() * self.scale
+    }
+}
+
+/// a simulation which happens later in time, created by `Simulation::delay`.
+///
+/// At time `t` the wrapped simulation is evaluated at `t - delay`. Before the delay has elapsed it holds
+/// still at the wrapped simulation's starting position.
+#[derive(Clone, Copy)]
+pub struct Delay<S, F = f32> {
+    simulation: S,
+    delay: F,
+}
+impl<S, F> Delay<S, F> {
+    pub(crate) fn new(simulation: S, delay: F) -> Delay<S, F> {
+        Delay { simulation, delay }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<S, F: Float> Delay<S, F> {
+    /// Return whether the delay has elapsed at the given time.
+    fn started(&self, time: F) -> bool {
+        time >= self.delay
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for Delay<S, F> {
+    fn x(&self, time: F) -> F {
+        self.simulation.x((time - self.delay).max(F::ZERO))
+    }
+    fn dx(&self, time: F) -> F {
+        if self.started(time) {
+            self.simulation.dx(time - self.delay)
+        } else {
+            F::ZERO
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        if self.started(time) {
+            self.simulation.ddx(time - self.delay)
+        } else {
+            F::ZERO
+        }
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.started(time) && self.simulation.is_done(time - self.delay)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time() + self.delay
+    }
+    fn final_position(&self) -> F {
+        self.simulation.final_position()
+    }
+}
+
+/// a simulation which runs faster or slower, created by `Simulation::time_scale`.
+///
+/// At time `t` the wrapped simulation is evaluated at `t * factor`, so a factor of 2.0 runs the simulation
+/// twice as fast. The velocity is scaled by the same factor.
+#[derive(Clone, Copy)]
+pub struct TimeScale<S, F = f32> {
+    simulation: S,
+    factor: F,
+}
+impl<S, F> TimeScale<S, F> {
+    pub(crate) fn new(simulation: S, factor: F) -> TimeScale<S, F> {
+        TimeScale { simulation, factor }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for TimeScale<S, F> {
+    fn x(&self, time: F) -> F {
+        self.simulation.x(time * self.factor)
+    }
+    fn dx(&self, time: F) -> F {
+        self.simulation.dx(time * self.factor) * self.factor
+    }
+    fn ddx(&self, time: F) -> F {
+        self.simulation.ddx(time * self.factor) * self.factor * self.factor
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.simulation.is_done(time * self.factor)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time() / self.factor
+    }
+    fn final_position(&self) -> F {
+        self.simulation.final_position()
+    }
+}
+
+/// a simulation with its position limited to a range, created by `Simulation::clamp`.
+///
+/// While the position is held at either end of the range the velocity is zero.
+#[derive(Clone, Copy)]
+pub struct Clamp<S, F = f32> {
+    simulation: S,
+    min: F,
+    max: F,
+}
+impl<S, F> Clamp<S, F> {
+    pub(crate) fn new(simulation: S, min: F, max: F) -> Clamp<S, F> {
+        Clamp {
+            simulation,
+            min,
+            max,
+        }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for Clamp<S, F> {
+    fn x(&self, time: F) -> F {
+        self.simulation.x(time).max(self.min).min(self.max)
+    }
+    fn dx(&self, time: F) -> F {
+        let x = self.simulation.x(time);
+        if x < self.min || x > self.max {
+            F::ZERO
+        } else {
+            self.simulation.dx(time)
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        let x = self.simulation.x(time);
+        if x < self.min || x > self.max {
+            F::ZERO
+        } else {
+            self.simulation.ddx(time)
+        }
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.simulation.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.simulation.final_position().max(self.min).min(self.max)
+    }
+}
+
+/// a simulation with its position transformed by a function, created by `Simulation::map`.
+///
+/// The velocity is computed with the chain rule, so the derivative of the function must also be supplied.
+/// The acceleration would need the second derivative of the function, so it is estimated from the velocity.
+#[derive(Clone, Copy)]
+pub struct Map<S, M, D> {
+    simulation: S,
+    map: M,
+    derivative: D,
+}
+impl<S, M, D> Map<S, M, D> {
+    pub(crate) fn new(simulation: S, map: M, derivative: D) -> Map<S, M, D> {
+        Map {
+            simulation,
+            map,
+            derivative,
+        }
+    }
+    /// Return the wrapped simulation.
+    pub fn inner(&self) -> &S {
+        &self.simulation
+    }
+    /// Return the wrapped simulation mutably, so that it can be set up again.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.simulation
+    }
+}
+impl<F, S, M, D> Simulation<F> for Map<S, M, D>
+where
+    F: Float,
+    S: Simulation<F>,
+    M: Fn(F) -> F,
+    D: Fn(F) -> F,
+{
+    fn x(&self, time: F) -> F {
+        (self.map)(self.simulation.x(time))
+    }
+    fn dx(&self, time: F) -> F {
+        (self.derivative)(self.simulation.x(time)) * self.simulation.dx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.simulation.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.simulation.settle_time()
+    }
+    fn final_position(&self) -> F {
+        (self.map)(self.simulation.final_position())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{Fling, Float, Resume, Simulation, Tolerance};
+
+/// a position that slows down by a constant rate every millisecond, like `UIScrollView`'s
+/// `decelerationRate`.
+///
+/// Each millisecond the velocity is multiplied by the rate, so a rate of 0.998 (`normal`) slows down gently
+/// and 0.99 (`fast`) stops quickly. This is the same shape as `Friction` (with a drag of `rate^1000`), but it
+/// uses UIKit's parameterization and travels exactly the distance given by `project`, which is what code
+/// ported from iOS expects. Its velocity at time zero is within a fraction of a percent of the velocity it
+/// was set with.
+#[derive(Clone, Copy)]
+pub struct Deceleration<F = f32> {
+    x: F,
+    distance: F, // from x to the final position.
+    rate: F,
+    ln_rate: F, // per second.
+    tolerance: Tolerance<F>,
+}
+impl<F: Float> Deceleration<F> {
+    /// Create a new deceleration with the given rate (per millisecond).
+    ///
+    /// The simulation is done once the velocity drops below 1.0, which suits values in points. Use
+    /// `set_tolerance` for values in other units.
+    pub fn new(rate: F) -> Deceleration<F> {
+        Deceleration {
+            x: F::ZERO,
+            distance: F::ZERO,
+            rate,
+            ln_rate: rate.ln() * F::from_f64(1000.0),
+            tolerance: Tolerance {
+                velocity: F::ONE,
+                ..Tolerance::default()
+            },
+        }
+    }
+    /// Create a new deceleration with UIKit's normal rate of 0.998.
+    pub fn normal() -> Deceleration<F> {
+        Deceleration::new(F::from_f64(0.998))
+    }
+    /// Create a new deceleration with UIKit's fast rate of 0.99.
+    pub fn fast() -> Deceleration<F> {
+        Deceleration::new(F::from_f64(0.99))
+    }
+    /// Set the initial (time = 0.0) position and velocity (per second).
+    pub fn set(&mut self, x: F, v: F) {
+        self.x = x;
+        self.distance = self.project(v);
+    }
+    /// Return the rate (per millisecond).
+    pub fn rate(&self) -> F {
+        self.rate
+    }
+    /// Return how far a fling with velocity `v` (per second) travels before it stops, using the formula
+    /// from UIKit: `(v / 1000) * rate / (1 - rate)`.
+    pub fn project(&self, v: F) -> F {
+        v / F::from_f64(1000.0) * self.rate / (F::ONE - self.rate)
+    }
+    /// Set the tolerance used to decide when the simulation is done. Only the velocity is used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+    }
+    /// Return the tolerance used to decide when the simulation is done.
+    pub fn tolerance(&self) -> Tolerance<F> {
+        self.tolerance
+    }
+    /// Return the time (in seconds) at which the simulation will reach the specified position. As with
+    /// `Friction`, this can be negative or NaN if the position is never reached.
+    pub fn time_for_position(&self, p: F) -> F {
+        if (p - self.x).abs() < F::EPSILON {
+            F::ZERO
+        } else {
+            (F::ONE - (p - self.x) / self.distance).ln() / self.ln_rate
+        }
+    }
+    /// Return the velocity (per second) which travels `distance` before it stops. This is the inverse of
+    /// `project`.
+    pub fn velocity_for_distance(&self, distance: F) -> F {
+        distance * F::from_f64(1000.0) * (F::ONE - self.rate) / self.rate
+    }
+    /// Return the rate (per millisecond) which makes a fling with velocity `v` travel `distance` before it
+    /// stops, or NaN if there isn't one (because the distance is zero, or in the other direction to the
+    /// velocity).
+    pub fn rate_for_distance(v: F, distance: F) -> F {
+        // Solve distance = (v / 1000) * rate / (1 - rate) for the rate.
+        let k = distance * F::from_f64(1000.0) / v;
+        if k > F::ZERO {
+            k / (F::ONE + k)
+        } else {
+            F::NAN
+        }
+    }
+}
+impl<F: Float> Simulation<F> for Deceleration<F> {
+    fn x(&self, time: F) -> F {
+        self.x + self.distance * (F::ONE - (self.ln_rate * time).exp())
+    }
+    fn dx(&self, time: F) -> F {
+        -self.distance * self.ln_rate * (self.ln_rate * time).exp()
+    }
+    fn ddx(&self, time: F) -> F {
+        self.dx(time) * self.ln_rate
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.dx(time).abs() < self.tolerance.velocity
+    }
+    fn settle_time(&self) -> F {
+        let v = self.dx(F::ZERO).abs();
+        if v < self.tolerance.velocity {
+            F::ZERO
+        } else {
+            // Step just past the time the velocity reaches the tolerance, so that it's below it.
+            (self.tolerance.velocity / v).ln() / self.ln_rate + self.tolerance.time
+        }
+    }
+    fn final_position(&self) -> F {
+        self.x + self.distance
+    }
+}
+impl<F: Float> Resume<F> for Deceleration<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
+impl<F: Float> Fling<F> for Deceleration<F> {
+    fn time_for_position(&self, p: F) -> F {
+        Deceleration::time_for_position(self, p)
+    }
+    fn velocity_for_distance(&self, distance: F) -> F {
+        Deceleration::velocity_for_distance(self, distance)
+    }
+    fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        Deceleration::set_tolerance(self, tolerance);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::search::{bisect, first_time_monotonic};
+use crate::{Float, Resume, Simulation, Tolerance};
+
+/// a position under gravity (or any other constant acceleration) which is slowed down by air drag, so that
+/// it approaches a terminal velocity rather than speeding up forever.
+///
+/// The drag is linear, so the acceleration is `a - drag * v`, which integrates to
+/// `v' = terminal + (v - terminal) * e^(-drag * t)` with a terminal velocity of `a / drag`. This is nice for
+/// long falls, such as dismissing a large dialog, which look wrong under `Gravity` alone.
+#[derive(Clone, Copy)]
+pub struct DragGravity<F = f32> {
+    x: F,
+    v: F,
+    a: F,
+    drag: F,
+    min: F,
+    max: F,
+    tolerance: Tolerance<F>,
+}
+impl<F: Float> DragGravity<F> {
+    /// Create a new simulation with the given acceleration and drag (per second), which must be positive.
+    pub fn new(a: F, drag: F) -> DragGravity<F> {
+        DragGravity {
+            x: F::ZERO,
+            v: F::ZERO,
+            a,
+            drag,
+            min: F::from_f64(-32000.0),
+            max: F::from_f64(32000.0),
+            tolerance: Tolerance::default(),
+        }
+    }
+    /// Create a new simulation with the given acceleration, and the drag which gives it the given terminal
+    /// speed.
+    pub fn with_terminal_velocity(a: F, terminal: F) -> DragGravity<F> {
+        DragGravity::new(a, (a / terminal).abs())
+    }
+    /// Set the initial position and velocity (in pixels per second).
+    pub fn set(&mut self, x: F, v: F) {
+        self.x = x;
+        self.v = v;
+    }
+    /// Return the acceleration.
+    pub fn acceleration(&self) -> F {
+        self.a
+    }
+    /// Return the drag (per second).
+    pub fn drag(&self) -> F {
+        self.drag
+    }
+    /// Return the velocity which the simulation approaches, `a / drag`.
+    pub fn terminal_velocity(&self) -> F {
+        self.a / self.drag
+    }
+    /// Set the positions below and above which the simulation is done, as with `Gravity::set_bounds`.
+    /// These are -32000.0 and 32000.0 by default, which suits values in pixels.
+    pub fn set_bounds(&mut self, min: F, max: F) {
+        self.min = min;
+        self.max = max;
+    }
+    /// Return the positions below and above which the simulation is done.
+    pub fn bounds(&self) -> (F, F) {
+        (self.min, self.max)
+    }
+    /// Set the distance from zero (in either direction) at which the simulation is done. This is the same
+    /// as `set_bounds(-stop, stop)`.
+    pub fn set_stop(&mut self, stop: F) {
+        self.set_bounds(-stop, stop);
+    }
+    /// Return the distance from zero at which the simulation is done, as given to `set_stop`. If the bounds
+    /// aren't symmetric this is the further of the two from zero.
+    pub fn stop(&self) -> F {
+        (-self.min).max(self.max)
+    }
+    /// Return the time (in seconds, from zero), position and velocity at which the simulation leaves its
+    /// bounds, or None if it never does. The position is the bound which it crosses, or the initial position
+    /// (at time zero) if it starts outside of them.
+    pub fn exit(&self) -> Option<(F, F, F)> {
+        if self.x <= self.min || self.x >= self.max {
+            return Some((F::ZERO, self.x, self.v));
+        }
+        let to_min = self.time_for_position(self.min);
+        let to_max = self.time_for_position(self.max);
+        // Take whichever bound is reached first; a NaN time means it never reaches that one.
+        let (time, bound) = if to_max.is_nan() || to_min <= to_max {
+            (to_min, self.min)
+        } else {
+            (to_max, self.max)
+        };
+        if time.is_nan() {
+            None
+        } else {
+            Some((time, bound, self.dx(time)))
+        }
+    }
+    /// Set the tolerance used to find the time for a position. Only the time is used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+    }
+    /// Return the first time (in seconds, from zero) at which the simulation reaches position `p`, or
+    /// NaN if it never does.
+    pub fn time_for_position(&self, p: F) -> F {
+        if p == self.x {
+            return F::ZERO;
+        }
+        let terminal = self.terminal_velocity();
+        // The velocity changes sign at most once, when it's thrown against the acceleration, so the
+        // position moves one way up to that time and the other way after it.
+        let mut start = F::ZERO;
+        if self.v * terminal < F::ZERO {
+            start = ((terminal - self.v) / terminal).ln() / self.drag;
+            let turn = self.x(start);
+            if (p - self.x) / self.v > F::ZERO && (turn - p) / self.v >= F::ZERO {
+                return bisect(F::ZERO, start, self.tolerance.time, |t| {
+                    (self.x(t) - p) / self.v >= F::ZERO
+                });
+            }
+        }
+        let heading = if terminal == F::ZERO {
+            self.v
+        } else {
+            terminal
+        };
+        if heading == F::ZERO || (p - self.x(start)) / heading < F::ZERO {
+            return F::NAN;
+        }
+        // Without an acceleration the drag stops it, as with `Friction`.
+        if terminal == F::ZERO && (self.x + self.v / self.drag - p) / heading < F::ZERO {
+            return F::NAN;
+        }
+        first_time_monotonic(start, self.tolerance.time, |t| {
+            (self.x(t) - p) / heading >= F::ZERO
+        })
+    }
+}
+impl<F: Float> Simulation<F> for DragGravity<F> {
+    fn x(&self, time: F) -> F {
+        let terminal = self.terminal_velocity();
+        self.x
+            + terminal * time
+            + (self.v - terminal) * (F::ONE - (-self.drag * time).exp()) / self.drag
+    }
+    fn dx(&self, time: F) -> F {
+        let terminal = self.terminal_velocity();
+        terminal + (self.v - terminal) * (-self.drag * time).exp()
+    }
+    fn ddx(&self, time: F) -> F {
+        self.a - self.drag * self.dx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        let x = self.x(time);
+        x <= self.min || x >= self.max
+    }
+    fn settle_time(&self) -> F {
+        if self.x <= self.min || self.x >= self.max {
+            return F::ZERO;
+        }
+        // Find the first time that we reach either bound.
+        self.time_for_position(self.min)
+            .min(self.time_for_position(self.max))
+    }
+}
+impl<F: Float> Resume<F> for DragGravity<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
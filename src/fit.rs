@@ -0,0 +1,301 @@
+use crate::{Float, Friction, Simulation, Spring};
+
+// Fitting simulations to recorded motion.
+//
+// Each simulation is a linear combination of a few functions of time, where the functions depend on the
+// simulation's parameters (such as the drag) and the coefficients are the start position, velocity and so on.
+// So for any parameters the best coefficients come from linear least squares, and the parameters themselves
+// are found by a Nelder-Mead search over the error that leaves.
+
+/// The spring which best matches a set of samples, found by `Spring::fit`.
+#[derive(Clone, Copy)]
+pub struct SpringFit<F = f32> {
+    /// The spring, set up to move as the samples do. Its time zero is the time of the first sample.
+    pub spring: Spring<F>,
+    /// The position of the spring at the first sample.
+    pub position: F,
+    /// The velocity of the spring at the first sample.
+    pub velocity: F,
+    /// The root mean square difference between the samples and the spring.
+    pub residual: F,
+}
+
+/// The friction which best matches a set of samples, found by `Friction::fit`.
+#[derive(Clone, Copy)]
+pub struct FrictionFit<F = f32> {
+    /// The friction, set up to move as the samples do. Its time zero is the time of the first sample.
+    pub friction: Friction<F>,
+    /// The position of the friction at the first sample.
+    pub position: F,
+    /// The velocity of the friction at the first sample.
+    pub velocity: F,
+    /// The root mean square difference between the samples and the friction.
+    pub residual: F,
+}
+
+impl<F: Float> Spring<F> {
+    /// Find the spring which best matches the given `(time, position)` samples, in the least squares sense.
+    ///
+    /// The stiffness, damping, end point and starting velocity are all fitted, along with the starting
+    /// position. The mass of a spring can't be told from its motion, so the spring has a mass of 1.0. Returns
+    /// None if there are fewer than five samples.
+    pub fn fit(samples: &[(F, F)]) -> Option<SpringFit<F>> {
+        if samples.len() < 5 {
+            return None;
+        }
+        let start = samples[0].0;
+        let span = samples[samples.len() - 1].0 - start;
+        // The spring's motion is a combination of releasing it from one unit away and throwing it from its
+        // end at one unit per second.
+        let basis = |log_frequency: F, log_ratio: F| {
+            let frequency = log_frequency.exp();
+            let ratio = log_ratio.exp();
+            let mut released = Spring::with_constants(
+                F::ONE,
+                frequency * frequency,
+                F::from_f64(2.0) * ratio * frequency,
+            );
+            let mut thrown = released;
+            released.snap(F::ONE);
+            released.set(F::ZERO, F::ZERO, F::ZERO);
+            thrown.set(F::ZERO, F::ONE, F::ZERO);
+            move |time: F| {
+                let t = time - start;
+                [F::ONE - released.x(t), released.x(t), thrown.x(t)]
+            }
+        };
+        let error =
+            |p: [F; 2]| least_squares(samples, basis(p[0], p[1])).map_or(F::INFINITY, |fit| fit.1);
+
+        // Start the search from the best of a few frequencies (compared to the length of the samples) and
+        // damping ratios.
+        let mut best = [F::ZERO, F::ZERO];
+        let mut best_error = F::INFINITY;
+        for &cycles in &[0.25, 0.5, 1.0, 2.0, 4.0, 8.0] {
+            for &ratio in &[0.1, 0.3, 0.6, 1.0, 2.0] {
+                let p = [
+                    (F::from_f64(cycles * 2.0) * F::PI / span).ln(),
+                    F::from_f64(ratio).ln(),
+                ];
+                let e = error(p);
+                if e < best_error {
+                    best = p;
+                    best_error = e;
+                }
+            }
+        }
+        let [log_frequency, log_ratio] = nelder_mead(best, F::from_f64(0.2), error);
+        let ([end, position, velocity], residual) =
+            least_squares(samples, basis(log_frequency, log_ratio))?;
+
+        let frequency = log_frequency.exp();
+        let damping = F::from_f64(2.0) * log_ratio.exp() * frequency;
+        let mut spring = Spring::with_constants(F::ONE, frequency * frequency, damping);
+        spring.snap(position);
+        spring.set(end, velocity, F::ZERO);
+        Some(SpringFit {
+            spring,
+            position,
+            velocity,
+            residual,
+        })
+    }
+}
+
+impl<F: Float> Friction<F> {
+    /// Find the friction which best matches the given `(time, position)` samples, in the least squares
+    /// sense.
+    ///
+    /// The drag, starting position and starting velocity are all fitted. Returns None if there are fewer
+    /// than three samples.
+    pub fn fit(samples: &[(F, F)]) -> Option<FrictionFit<F>> {
+        if samples.len() < 3 {
+            return None;
+        }
+        let start = samples[0].0;
+        let span = samples[samples.len() - 1].0 - start;
+        // The position is x + v * (drag^t - 1) / ln(drag), and we search over ln(-ln(drag)).
+        let basis = |log_rate: F| {
+            let ln_drag = -log_rate.exp();
+            move |time: F| {
+                [
+                    F::ONE,
+                    ((ln_drag * (time - start)).exp() - F::ONE) / ln_drag,
+                ]
+            }
+        };
+        let error =
+            |p: [F; 1]| least_squares(samples, basis(p[0])).map_or(F::INFINITY, |fit| fit.1);
+
+        // Start the search from the best of a few rates of decay, compared to the length of the samples.
+        let mut best = [F::ZERO];
+        let mut best_error = F::INFINITY;
+        for &decays in &[0.01, 0.1, 0.3, 1.0, 3.0, 10.0, 30.0] {
+            let p = [(F::from_f64(decays) / span).ln()];
+            let e = error(p);
+            if e < best_error {
+                best = p;
+                best_error = e;
+            }
+        }
+        let [log_rate] = nelder_mead(best, F::from_f64(0.2), error);
+        let ([position, velocity], residual) = least_squares(samples, basis(log_rate))?;
+
+        let mut friction = Friction::with_drag((-log_rate.exp()).exp());
+        friction.set(position, velocity);
+        Some(FrictionFit {
+            friction,
+            position,
+            velocity,
+            residual,
+        })
+    }
+}
+
+/// Find the coefficients of the combination of the `basis` functions which best matches the samples, and
+/// the root mean square error of that combination. Returns None if the coefficients can't be found.
+fn least_squares<F: Float, const N: usize>(
+    samples: &[(F, F)],
+    basis: impl Fn(F) -> [F; N],
+) -> Option<([F; N], F)> {
+    // Build the normal equations, A * c = b.
+    let mut a = [[F::ZERO; N]; N];
+    let mut b = [F::ZERO; N];
+    for &(time, x) in samples {
+        let f = basis(time);
+        for i in 0..N {
+            for j in 0..N {
+                a[i][j] += f[i] * f[j];
+            }
+            b[i] += f[i] * x;
+        }
+    }
+    // Solve them by Gaussian elimination with partial pivoting.
+    for column in 0..N {
+        let mut pivot = column;
+        for row in column + 1..N {
+            if a[row][column].abs() > a[pivot][column].abs() {
+                pivot = row;
+            }
+        }
+        if a[pivot][column] == F::ZERO || a[pivot][column].is_nan() {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let (pivot_row, pivot_b) = (a[column], b[column]);
+        for row in column + 1..N {
+            let factor = a[row][column] / pivot_row[column];
+            for (value, pivot_value) in a[row].iter_mut().zip(pivot_row.iter()).skip(column) {
+                *value -= factor * *pivot_value;
+            }
+            b[row] -= factor * pivot_b;
+        }
+    }
+    let mut c = [F::ZERO; N];
+    for row in (0..N).rev() {
+        let mut sum = b[row];
+        for k in row + 1..N {
+            sum -= a[row][k] * c[k];
+        }
+        c[row] = sum / a[row][row];
+    }
+
+    let mut error = F::ZERO;
+    for &(time, x) in samples {
+        let f = basis(time);
+        let mut fitted = F::ZERO;
+        for i in 0..N {
+            fitted += c[i] * f[i];
+        }
+        error += (fitted - x) * (fitted - x);
+    }
+    let residual = (error / F::from_f64(samples.len() as f64)).sqrt();
+    if residual.is_finite() {
+        Some((c, residual))
+    } else {
+        None
+    }
+}
+
+/// Find a minimum of `f` near `start` with the Nelder-Mead simplex method, starting with a simplex of the
+/// given size. The simplex is kept in a fixed array, so this only works for up to two parameters.
+fn nelder_mead<F: Float, const N: usize>(
+    start: [F; N],
+    size: F,
+    f: impl Fn([F; N]) -> F,
+) -> [F; N] {
+    let half = F::from_f64(0.5);
+    let two = F::from_f64(2.0);
+    // The simplex has N + 1 points, which are kept with their values. The first point is `start`.
+    // There's room for the simplexes used here, which have at most three points.
+    debug_assert!(N <= 2, "nelder_mead only has room for two parameters");
+    let mut points = [(start, f(start)); 3];
+    for i in 0..N {
+        let mut p = start;
+        p[i] += size;
+        points[i + 1] = (p, f(p));
+    }
+    let simplex = &mut points[..=N];
+    let mix = |a: [F; N], b: [F; N], t: F| {
+        let mut p = a;
+        for (p, b) in p.iter_mut().zip(b.iter()) {
+            *p += (*b - *p) * t;
+        }
+        p
+    };
+    for _ in 0..400 {
+        simplex
+            .sort_unstable_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(core::cmp::Ordering::Equal));
+        let (worst, worst_value) = simplex[N];
+        // Stop once the simplex has collapsed.
+        let best = simplex[0].0;
+        let mut spread = F::ZERO;
+        for (p, _) in simplex.iter() {
+            for (x, best) in p.iter().zip(best.iter()) {
+                spread = spread.max((*x - *best).abs());
+            }
+        }
+        if spread < F::EPSILON.sqrt() {
+            break;
+        }
+        let mut centroid = [F::ZERO; N];
+        for (p, _) in simplex[..N].iter() {
+            for (c, x) in centroid.iter_mut().zip(p.iter()) {
+                *c += *x / F::from_f64(N as f64);
+            }
+        }
+        let reflected = mix(centroid, worst, -F::ONE);
+        let reflected_value = f(reflected);
+        if reflected_value < simplex[0].1 {
+            let expanded = mix(centroid, worst, -two);
+            let expanded_value = f(expanded);
+            simplex[N] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[N - 1].1 {
+            simplex[N] = (reflected, reflected_value);
+        } else {
+            let contracted = if reflected_value < worst_value {
+                mix(centroid, reflected, half)
+            } else {
+                mix(centroid, worst, half)
+            };
+            let contracted_value = f(contracted);
+            if contracted_value < worst_value.min(reflected_value) {
+                simplex[N] = (contracted, contracted_value);
+            } else {
+                // Shrink everything towards the best point.
+                let best = simplex[0].0;
+                for point in simplex[1..].iter_mut() {
+                    let p = mix(best, point.0, half);
+                    *point = (p, f(p));
+                }
+            }
+        }
+    }
+    simplex.sort_unstable_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(core::cmp::Ordering::Equal));
+    simplex[0].0
+}
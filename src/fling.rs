@@ -0,0 +1,17 @@
+use crate::{Float, Resume, Simulation, Tolerance};
+
+/// a simulation which slows a flung position down to a stop, which `Scroll` and `Pager` use between their
+/// springs.
+///
+/// `Friction` is used unless another model is given with `Scroll::with_fling` or `Pager::with_fling`. The
+/// fling is started with `Resume::resume`.
+pub trait Fling<F: Float = f32>: Simulation<F> + Resume<F> {
+    /// Return the time (in seconds) at which the position reaches `p`. This can be negative (if the position
+    /// would have been reached before time zero going the other way) or NaN if the position is never reached.
+    fn time_for_position(&self, p: F) -> F;
+    /// Return the velocity which a fling needs to stop `distance` from where it starts (so it has the same
+    /// sign as the distance).
+    fn velocity_for_distance(&self, distance: F) -> F;
+    /// Set the tolerance used to decide when the fling is done.
+    fn set_tolerance(&mut self, tolerance: Tolerance<F>);
+}
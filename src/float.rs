@@ -0,0 +1,141 @@
+use core::fmt::Debug;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// the floating point type that a simulation is computed with.
+///
+/// This is implemented for `f32` (the default for every simulation) and `f64`. Using `f64` is
+/// worthwhile for long running animations, where the simulation time gets large, or for very long
+/// scroll extents where `f32` can no longer represent small movements far from the origin.
+pub trait Float:
+    Copy
+    + Debug
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    /// Zero.
+    const ZERO: Self;
+    /// One.
+    const ONE: Self;
+    /// The difference between 1.0 and the next representable value.
+    const EPSILON: Self;
+    /// Not a number.
+    const NAN: Self;
+    /// Positive infinity.
+    const INFINITY: Self;
+    /// Euler's number.
+    const E: Self;
+    /// Archimedes' constant.
+    const PI: Self;
+
+    /// Convert from an `f64`, rounding if required. This is used for the constants in the equations.
+    fn from_f64(value: f64) -> Self;
+    /// Convert to an `f64`.
+    fn to_f64(self) -> f64;
+    /// Absolute value.
+    fn abs(self) -> Self;
+    /// Square root.
+    fn sqrt(self) -> Self;
+    /// Natural logarithm.
+    fn ln(self) -> Self;
+    /// `e` raised to the power of this value.
+    fn exp(self) -> Self;
+    /// This value raised to the power of `n`.
+    fn powf(self, n: Self) -> Self;
+    /// Sine (in radians).
+    fn sin(self) -> Self;
+    /// Cosine (in radians).
+    fn cos(self) -> Self;
+    /// Four quadrant arctangent of `self` (y) and `x`.
+    fn atan2(self, x: Self) -> Self;
+    /// Returns true if this value is not a number.
+    fn is_nan(self) -> bool;
+    /// Returns true if this value is neither infinite nor not a number.
+    fn is_finite(self) -> bool;
+    /// The smaller of two values, ignoring NaN.
+    fn min(self, other: Self) -> Self;
+    /// The larger of two values, ignoring NaN.
+    fn max(self, other: Self) -> Self;
+}
+
+// With `std` the inherent float methods are used, otherwise the math comes from `libm`.
+#[cfg(feature = "std")]
+macro_rules! math {
+    ($t:ident, $std:ident, $libm:ident, $($arg:expr),+) => {
+        $t::$std($($arg),+)
+    };
+}
+#[cfg(not(feature = "std"))]
+macro_rules! math {
+    ($t:ident, $std:ident, $libm:ident, $($arg:expr),+) => {
+        libm::$libm($($arg),+)
+    };
+}
+
+macro_rules! impl_float {
+    ($t:ident, $fabs:ident, $sqrt:ident, $log:ident, $exp:ident, $pow:ident, $sin:ident, $cos:ident, $atan2:ident, $fmin:ident, $fmax:ident) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const EPSILON: Self = $t::EPSILON;
+            const NAN: Self = $t::NAN;
+            const INFINITY: Self = $t::INFINITY;
+            const E: Self = core::$t::consts::E;
+            const PI: Self = core::$t::consts::PI;
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn abs(self) -> Self {
+                math!($t, abs, $fabs, self)
+            }
+            fn sqrt(self) -> Self {
+                math!($t, sqrt, $sqrt, self)
+            }
+            fn ln(self) -> Self {
+                math!($t, ln, $log, self)
+            }
+            fn exp(self) -> Self {
+                math!($t, exp, $exp, self)
+            }
+            fn powf(self, n: Self) -> Self {
+                math!($t, powf, $pow, self, n)
+            }
+            fn sin(self) -> Self {
+                math!($t, sin, $sin, self)
+            }
+            fn cos(self) -> Self {
+                math!($t, cos, $cos, self)
+            }
+            fn atan2(self, x: Self) -> Self {
+                math!($t, atan2, $atan2, self, x)
+            }
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+            fn is_finite(self) -> bool {
+                $t::is_finite(self)
+            }
+            fn min(self, other: Self) -> Self {
+                math!($t, min, $fmin, self, other)
+            }
+            fn max(self, other: Self) -> Self {
+                math!($t, max, $fmax, self, other)
+            }
+        }
+    };
+}
+
+impl_float!(f32, fabsf, sqrtf, logf, expf, powf, sinf, cosf, atan2f, fminf, fmaxf);
+impl_float!(f64, fabs, sqrt, log, exp, pow, sin, cos, atan2, fmin, fmax);
@@ -0,0 +1,119 @@
+use crate::{Float, MovingSpring, Resume, Simulation, Spring, Tolerance};
+use core::cell::Cell;
+
+/// a spring which chases the position of another simulation (the leader).
+///
+/// Time is split into fixed steps (1/120 of a second unless changed with `set_step`). During each step the
+/// leader is taken to move in a straight line between its positions at either end of the step, and the
+/// follower moves exactly as a `MovingSpring` chasing that line would. Because the steps don't depend on
+/// when the follower is sampled, it moves the same way at any frame rate.
+///
+/// Finding the position at a time means stepping forward from the last time asked for (or from the start,
+/// when going backwards), so the follower is cheapest when sampled in order.
+pub struct Follower<S, F = f32> {
+    leader: S,
+    spring: MovingSpring<F>,
+    start: (F, F), // position and velocity at time zero.
+    step: F,
+    tolerance: Tolerance<F>,
+    state: Cell<(usize, F, F)>, // the last step reached, with the position and velocity at its start.
+}
+impl<F: Float, S: Simulation<F>> Follower<S, F> {
+    /// Create a new follower which chases `leader` with the mass, spring constant, damping and tolerance of
+    /// `spring`. The follower starts from the position and velocity that `spring` has at time zero.
+    pub fn new(leader: S, spring: Spring<F>) -> Follower<S, F> {
+        let mut moving =
+            MovingSpring::new(spring.mass(), spring.spring_constant(), spring.damping());
+        moving.set_tolerance(spring.tolerance());
+        let start = (spring.x(F::ZERO), spring.dx(F::ZERO));
+        Follower {
+            leader,
+            spring: moving,
+            start,
+            step: F::ONE / F::from_f64(120.0),
+            tolerance: spring.tolerance(),
+            state: Cell::new((0, start.0, start.1)),
+        }
+    }
+    /// Set the length of the steps (in seconds) over which the leader is taken to move in a straight line.
+    /// Steps which aren't positive and finite are ignored, keeping the previous step.
+    pub fn set_step(&mut self, step: F) {
+        if step > F::ZERO && step.is_finite() {
+            self.step = step;
+            self.restart();
+        }
+    }
+    /// Return the simulation being followed.
+    pub fn leader(&self) -> &S {
+        &self.leader
+    }
+    /// Return the simulation being followed mutably, so that it can be set up again.
+    pub fn leader_mut(&mut self) -> &mut S {
+        self.restart();
+        &mut self.leader
+    }
+    fn restart(&mut self) {
+        self.state.set((0, self.start.0, self.start.1));
+    }
+    /// Return the spring chasing the leader during the step containing `time`, with its time starting
+    /// from zero at the start of the step.
+    fn spring_at(&self, time: F) -> (MovingSpring<F>, F) {
+        let (mut index, mut x, mut v) = self.state.get();
+        let step_start = |index: usize| F::from_f64(index as f64) * self.step;
+        if time < step_start(index) {
+            index = 0;
+            x = self.start.0;
+            v = self.start.1;
+        }
+        loop {
+            let (from, to) = (step_start(index), step_start(index + 1));
+            let end = self.leader.x(from);
+            let mut spring = self.spring;
+            spring.set(end, (self.leader.x(to) - end) / self.step, F::ZERO);
+            spring.resume(x, v);
+            if time < to || !time.is_finite() {
+                self.state.set((index, x, v));
+                return (spring, time - from);
+            }
+            x = spring.x(self.step);
+            v = spring.dx(self.step);
+            index += 1;
+        }
+    }
+}
+impl<F: Float, S: Clone> Clone for Follower<S, F> {
+    fn clone(&self) -> Follower<S, F> {
+        Follower {
+            leader: self.leader.clone(),
+            spring: self.spring,
+            start: self.start,
+            step: self.step,
+            tolerance: self.tolerance,
+            state: self.state.clone(),
+        }
+    }
+}
+impl<F: Float, S: Simulation<F>> Simulation<F> for Follower<S, F> {
+    fn x(&self, time: F) -> F {
+        let (spring, local) = self.spring_at(time);
+        spring.x(local)
+    }
+    fn dx(&self, time: F) -> F {
+        let (spring, local) = self.spring_at(time);
+        spring.dx(local)
+    }
+    fn ddx(&self, time: F) -> F {
+        let (spring, local) = self.spring_at(time);
+        spring.ddx(local)
+    }
+    /// The follower is done once the leader is done and the follower has caught up with it.
+    fn is_done(&self, time: F) -> bool {
+        let (spring, local) = self.spring_at(time);
+        self.leader.is_done(time)
+            && (spring.x(local) - self.leader.x(time)).abs() < self.tolerance.position
+            && (spring.dx(local) - self.leader.dx(time)).abs() < self.tolerance.velocity
+    }
+    fn final_position(&self) -> F {
+        self.leader.final_position()
+    }
+}
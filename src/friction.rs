@@ -1,33 +1,61 @@
-use crate::Simulation;
+use crate::{Fling, Float, Resume, Simulation, Tolerance};
 
 /// a position with velocity that slows down due to drag.
 ///
 /// This is good for objects that you fling&mdash;the scroll simulation uses this model in combination
 /// with a spring. It can also be used in combination with a constant velocity for infinite
-/// carousels, such as this one: <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/iTunesRadio/">Gravitas JavaScript Friction Example</a>.
+/// carousels, such as this one: <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/iTunesRadio/">Gravitas JavaScript Friction Example</a>,
+/// which is what `Carousel` does.
 #[derive(Copy, Clone)]
-pub struct Friction {
-    x: f32,
-    v: f32,
-    drag: f32,
-    ln_drag: f32,
+pub struct Friction<F = f32> {
+    x: F,
+    v: F,
+    drag: F,
+    ln_drag: F,
+    tolerance: Tolerance<F>,
 }
-impl Friction {
+impl Friction<f32> {
     /// Create a new friction simulation with the given drag value. For scrolling interfaces where
     /// values are in pixels, a drag value of 0.001 feels quite good.
-    pub fn new(drag: f32) -> Friction {
+    ///
+    /// The simulation is done once the velocity drops below 1.0, which suits values in pixels. Use
+    /// `set_tolerance` for values in other units. This makes an `f32` simulation; use `with_drag` for
+    /// other types.
+    pub fn new(drag: f32) -> Friction<f32> {
+        Friction::with_drag(drag)
+    }
+}
+impl<F: Float> Friction<F> {
+    /// Create a new friction simulation with the given drag value, like `new` but for any `Float` type.
+    pub fn with_drag(drag: F) -> Friction<F> {
         Friction {
-            x: 0.0,
-            v: 0.0,
+            x: F::ZERO,
+            v: F::ZERO,
             drag,
             ln_drag: drag.ln(),
+            tolerance: Tolerance {
+                velocity: F::ONE,
+                ..Tolerance::default()
+            },
         }
     }
     /// Set the initial (time = 0.0) position and velocity for the friction simulation.
-    pub fn set(&mut self, x: f32, v: f32) {
+    pub fn set(&mut self, x: F, v: F) {
         self.x = x;
         self.v = v;
     }
+    /// Return the drag value.
+    pub fn drag(&self) -> F {
+        self.drag
+    }
+    /// Set the tolerance used to decide when the simulation is done. Only the velocity is used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+    }
+    /// Return the tolerance used to decide when the simulation is done.
+    pub fn tolerance(&self) -> Tolerance<F> {
+        self.tolerance
+    }
     /// Return the time (in seconds) at which the friction simulation will reach the specified position. This
     /// value can be negative (which means the simulation would have reached that position if the velocity had
     /// been in the other direction) or not a number (NaN) which means the simulation will never reach that position.
@@ -35,22 +63,67 @@ impl Friction {
     /// This method is used by the scroll simulation to find out the exact time that the scroll position will
     /// go beyond the scroll extent (at which time the velocity is put into a spring simulation which bounces the
     /// scroll position back to the extent).
-    pub fn time_for_position(&self, p: f32) -> f32 {
-        if (p - self.x).abs() < std::f32::EPSILON {
-            0.0
+    pub fn time_for_position(&self, p: F) -> F {
+        if (p - self.x).abs() < F::EPSILON {
+            F::ZERO
         } else {
             (((p - self.x) * self.ln_drag + self.v) / self.v).ln() / self.ln_drag
         }
     }
+    /// Return the velocity which stops `distance` from where it starts. This is the inverse of
+    /// `final_position`, and can be used to make a fling land exactly on a boundary.
+    pub fn velocity_for_distance(&self, distance: F) -> F {
+        -distance * self.ln_drag
+    }
+    /// Return the drag which makes a fling with velocity `v` stop `distance` away, or NaN if there isn't one
+    /// (because the distance is zero, or in the other direction to the velocity).
+    pub fn drag_for_distance(v: F, distance: F) -> F {
+        if v / distance > F::ZERO {
+            (-v / distance).exp()
+        } else {
+            F::NAN
+        }
+    }
 }
-impl Simulation for Friction {
-    fn x(&self, time: f32) -> f32 {
+impl<F: Float> Simulation<F> for Friction<F> {
+    fn x(&self, time: F) -> F {
         self.x + self.v * self.drag.powf(time) / self.ln_drag - self.v / self.ln_drag
     }
-    fn dx(&self, time: f32) -> f32 {
+    fn dx(&self, time: F) -> F {
         self.v * self.drag.powf(time)
     }
-    fn is_done(&self, time: f32) -> bool {
-        self.dx(time).abs() < 1.0
+    fn ddx(&self, time: F) -> F {
+        self.v * self.ln_drag * self.drag.powf(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.dx(time).abs() < self.tolerance.velocity
+    }
+    fn settle_time(&self) -> F {
+        // Solve v * drag^t = tolerance for t.
+        if self.v.abs() < self.tolerance.velocity {
+            F::ZERO
+        } else {
+            (self.tolerance.velocity / self.v.abs()).ln() / self.ln_drag
+        }
+    }
+    fn final_position(&self) -> F {
+        // The limit of x as time goes to infinity, since drag^t goes to zero.
+        self.x - self.v / self.ln_drag
+    }
+}
+impl<F: Float> Resume<F> for Friction<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
+impl<F: Float> Fling<F> for Friction<F> {
+    fn time_for_position(&self, p: F) -> F {
+        Friction::time_for_position(self, p)
+    }
+    fn velocity_for_distance(&self, distance: F) -> F {
+        Friction::velocity_for_distance(self, distance)
+    }
+    fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        Friction::set_tolerance(self, tolerance);
     }
 }
@@ -1,45 +1,156 @@
-use crate::Simulation;
+use crate::{Float, Resume, Simulation};
 
 /// a position under the influence of gravity (or any other constant acceleration), as defined by Newton's 2nd Law: `F = ma`.
 ///
 /// Newton's 2nd law, `F = ma`, integrates to `x' = x + v * t + 0.5 * a * t * t`, which is what this simulation uses to compute a position.
 ///
-/// This simulation is nice for objects that are falling, or have to overcome gravity in some way.
+/// This simulation is nice for objects that are falling, or have to overcome gravity in some way. For long falls,
+/// `DragGravity` adds air drag so that objects don't keep speeding up.
 /// * gravity combined with a spring simulation to make bouncy dialog boxes: <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/FallingDialogs/index.html">Gravitas JavaScript bouncy dialogs</a>.
 /// * gravity used to make a lock screen, which must be dragged upwards to unlock: <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/LockScreen/index.html">Gravitas JavaScript lock screen</a>.
 #[derive(Clone, Copy)]
-pub struct Gravity {
-    x: f32,
-    v: f32,
-    a: f32,
-    stop: f32, // In case the gravity runs away with something.
+pub struct Gravity<F = f32> {
+    x: F,
+    v: F,
+    a: F,
+    // In case the gravity runs away with something.
+    min: F,
+    max: F,
 }
-impl Gravity {
+impl Gravity<f32> {
     /// Create a new gravity siulation with the given acceleration. A value
     /// of 500 \* 9.8 (so 500px corresponds to 1 meter) is normally a good
     /// starting point.
-    pub fn new(a: f32) -> Gravity {
+    ///
+    /// This makes an `f32` simulation; use `with_acceleration` for other types.
+    pub fn new(a: f32) -> Gravity<f32> {
+        Gravity::with_acceleration(a)
+    }
+}
+impl<F: Float> Gravity<F> {
+    /// Create a new gravity simulation with the given acceleration, like `new` but for any `Float` type.
+    pub fn with_acceleration(a: F) -> Gravity<F> {
         Gravity {
-            x: 0.0,
-            v: 0.0,
+            x: F::ZERO,
+            v: F::ZERO,
             a,
-            stop: 32000.0,
+            min: F::from_f64(-32000.0),
+            max: F::from_f64(32000.0),
         }
     }
     /// Set the initial position and velocity (in pixels per second) of the gravity simulation.
-    pub fn set(&mut self, x: f32, v: f32) {
+    pub fn set(&mut self, x: F, v: F) {
         self.x = x;
         self.v = v;
     }
+    /// Return the acceleration.
+    pub fn acceleration(&self) -> F {
+        self.a
+    }
+    /// Set the positions below and above which the simulation is done, such as the edges of the viewport.
+    /// These are -32000.0 and 32000.0 by default, which suits values in pixels.
+    pub fn set_bounds(&mut self, min: F, max: F) {
+        self.min = min;
+        self.max = max;
+    }
+    /// Return the positions below and above which the simulation is done.
+    pub fn bounds(&self) -> (F, F) {
+        (self.min, self.max)
+    }
+    /// Set the distance from zero (in either direction) at which the simulation is done. This is the same
+    /// as `set_bounds(-stop, stop)`.
+    pub fn set_stop(&mut self, stop: F) {
+        self.set_bounds(-stop, stop);
+    }
+    /// Return the distance from zero at which the simulation is done, as given to `set_stop`. If the bounds
+    /// aren't symmetric this is the further of the two from zero.
+    pub fn stop(&self) -> F {
+        (-self.min).max(self.max)
+    }
+    /// Return the time (in seconds, from zero), position and velocity at which the simulation leaves its
+    /// bounds, or None if it never does. The position is the bound which it crosses, or the initial position
+    /// (at time zero) if it starts outside of them.
+    pub fn exit(&self) -> Option<(F, F, F)> {
+        if self.x <= self.min || self.x >= self.max {
+            return Some((F::ZERO, self.x, self.v));
+        }
+        let to_min = self.time_for_position(self.min);
+        let to_max = self.time_for_position(self.max);
+        // Take whichever bound is reached first; a NaN time means it never reaches that one.
+        let (time, bound) = if to_max.is_nan() || to_min <= to_max {
+            (to_min, self.min)
+        } else {
+            (to_max, self.max)
+        };
+        if time.is_nan() {
+            None
+        } else {
+            Some((time, bound, self.dx(time)))
+        }
+    }
+    /// Return the first time (in seconds, from zero) at which the simulation reaches position `p`, or
+    /// NaN if it never does. This is the earliest non-negative root of `x + v * t + 0.5 * a * t * t = p`.
+    pub fn time_for_position(&self, p: F) -> F {
+        let (first, second) = quadratic_roots(F::from_f64(0.5) * self.a, self.v, self.x - p);
+        if first.is_finite() && first >= F::ZERO {
+            first
+        } else if second.is_finite() && second >= F::ZERO {
+            second
+        } else {
+            F::NAN
+        }
+    }
 }
-impl Simulation for Gravity {
-    fn x(&self, time: f32) -> f32 {
-        self.x + self.v * time + 0.5 * self.a * time * time
+impl<F: Float> Simulation<F> for Gravity<F> {
+    fn x(&self, time: F) -> F {
+        self.x + self.v * time + F::from_f64(0.5) * self.a * time * time
     }
-    fn dx(&self, time: f32) -> f32 {
+    fn dx(&self, time: F) -> F {
         self.v + self.a * time
     }
-    fn is_done(&self, time: f32) -> bool {
-        self.x(time).abs() >= self.stop
+    fn ddx(&self, _time: F) -> F {
+        self.a
+    }
+    fn is_done(&self, time: F) -> bool {
+        let x = self.x(time);
+        x <= self.min || x >= self.max
+    }
+    fn settle_time(&self) -> F {
+        if self.x <= self.min || self.x >= self.max {
+            return F::ZERO;
+        }
+        // Find the first time that we reach either bound.
+        self.time_for_position(self.min)
+            .min(self.time_for_position(self.max))
+    }
+}
+
+impl<F: Float> Resume<F> for Gravity<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
+
+/// Return the real roots of `a * t * t + b * t + c = 0` (smallest first), or NaN for roots that don't exist.
+pub(crate) fn quadratic_roots<F: Float>(a: F, b: F, c: F) -> (F, F) {
+    if a == F::ZERO {
+        // It's linear, and there's at most one root.
+        return (-c / b, F::NAN);
+    }
+    let discriminant = b * b - F::from_f64(4.0) * a * c;
+    if discriminant < F::ZERO {
+        return (F::NAN, F::NAN);
     }
+    // Avoid cancellation between b and the square root.
+    let q = if b >= F::ZERO {
+        -F::from_f64(0.5) * (b + discriminant.sqrt())
+    } else {
+        -F::from_f64(0.5) * (b - discriminant.sqrt())
+    };
+    let (r1, r2) = if q == F::ZERO {
+        (F::ZERO, F::ZERO)
+    } else {
+        (q / a, c / q)
+    };
+    (r1.min(r2), r1.max(r2))
 }
@@ -0,0 +1,182 @@
+use crate::search::first_time;
+use crate::{Fling, Float, Simulation, Spring, Tolerance};
+
+/// a simulation which can be started again from a given position and velocity.
+///
+/// This is what lets `Handoff` carry the position and velocity of its first simulation over to its second.
+pub trait Resume<F: Float = f32> {
+    /// Start the simulation from position `x` with velocity `v` at time zero. Any other configuration
+    /// (such as a spring's end point or a scroll's extent) is kept.
+    fn resume(&mut self, x: F, v: F);
+}
+
+/// the event which makes a `Handoff` switch from its first simulation to its second.
+#[derive(Clone, Copy, Debug)]
+pub enum Trigger<F = f32> {
+    /// Switch at the given time (in seconds). A time that isn't finite means never switch.
+    Time(F),
+    /// Switch when the position of the first simulation reaches the given value.
+    Position(F),
+    /// Switch when the speed of the first simulation reaches the given value, whether it's slowing down
+    /// or speeding up.
+    Velocity(F),
+}
+
+/// a simulation that runs one simulation and then switches to another when a trigger fires.
+///
+/// This is how `Scroll` is built: friction runs until the scroll position reaches an extent, and then a
+/// spring takes over, starting with the position and velocity that the friction had at that time. With
+/// `Handoff` any two simulations can be combined in this way, provided the second one implements `Resume`.
+///
+/// The second simulation runs in its own time, starting from zero at the moment of the switch.
+#[derive(Clone, Copy)]
+pub struct Handoff<A, B, F = f32> {
+    first: A,
+    second: B,
+    switch_time: F,
+    tolerance: Tolerance<F>,
+}
+impl<F: Float, A: Simulation<F>, B: Simulation<F> + Resume<F>> Handoff<A, B, F> {
+    /// Create a new handoff simulation which runs `first` until `trigger` fires and then continues with
+    /// `second`. If the trigger never fires then `first` runs forever.
+    ///
+    /// Position and velocity triggers are found by searching the first simulation, which stops once the
+    /// first simulation is done.
+    pub fn new(first: A, second: B, trigger: Trigger<F>) -> Handoff<A, B, F> {
+        Handoff::with_tolerance(first, second, trigger, Tolerance::default())
+    }
+    /// Create a new handoff simulation, like `new`, which finds position and velocity triggers to within
+    /// the time of the given tolerance.
+    pub fn with_tolerance(
+        first: A,
+        second: B,
+        trigger: Trigger<F>,
+        tolerance: Tolerance<F>,
+    ) -> Handoff<A, B, F> {
+        let mut handoff = Handoff {
+            first,
+            second,
+            switch_time: F::NAN,
+            tolerance,
+        };
+        handoff.set_trigger(trigger);
+        handoff
+    }
+    /// Find when the given trigger fires, and start the second simulation from the position and velocity
+    /// that the first simulation has then. This is how to hand off again after changing either simulation.
+    pub fn set_trigger(&mut self, trigger: Trigger<F>) {
+        let first = &self.first;
+        self.switch_time = match trigger {
+            Trigger::Time(time) => time,
+            Trigger::Position(p) => {
+                let start = first.x(F::ZERO) - p;
+                first_time(
+                    F::ZERO,
+                    self.tolerance.time,
+                    |t| (first.x(t) - p) * start <= F::ZERO,
+                    |t| first.is_done(t),
+                )
+            }
+            Trigger::Velocity(v) => {
+                let start = first.dx(F::ZERO).abs() - v;
+                first_time(
+                    F::ZERO,
+                    self.tolerance.time,
+                    |t| (first.dx(t).abs() - v) * start <= F::ZERO,
+                    |t| first.is_done(t),
+                )
+            }
+        };
+        if self.switch_time.is_finite() {
+            let (x, v) = (first.x(self.switch_time), first.dx(self.switch_time));
+            self.second.resume(x, v);
+        }
+    }
+    /// Return the time at which the second simulation takes over, or NaN if it never does.
+    pub fn switch_time(&self) -> F {
+        self.switch_time
+    }
+    /// Return the simulation used before the switch.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+    /// Return the simulation used after the switch. Its time starts at zero at the switch.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+    /// Return the simulation used before the switch mutably, so that it can be set up again. The switch
+    /// isn't found again until `set_trigger` is called.
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+    /// Return the simulation used after the switch mutably, for example to change a spring's end point
+    /// before calling `set_trigger`.
+    pub fn second_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+    fn in_second(&self, time: F) -> bool {
+        self.switch_time.is_finite() && time >= self.switch_time
+    }
+}
+impl<F: Float, A: Fling<F>> Handoff<A, Spring<F>, F> {
+    /// Return the first time (in seconds, from zero) at which the position reaches `p`, or NaN if it
+    /// never does, for a fling which hands off to a spring. This looks at the fling first, and then at the
+    /// spring (including any bounces).
+    pub fn time_for_position(&self, p: F) -> F {
+        let fling_time = self.first.time_for_position(p);
+        if fling_time.is_finite() && fling_time >= F::ZERO && !self.in_second(fling_time) {
+            return fling_time;
+        }
+        if !self.switch_time.is_finite() {
+            return F::NAN;
+        }
+        match self.second.crossings(p).next() {
+            Some(time) => self.switch_time + time,
+            None => F::NAN,
+        }
+    }
+}
+impl<F: Float, A: Simulation<F>, B: Simulation<F> + Resume<F>> Simulation<F> for Handoff<A, B, F> {
+    fn x(&self, time: F) -> F {
+        if self.in_second(time) {
+            self.second.x(time - self.switch_time)
+        } else {
+            self.first.x(time)
+        }
+    }
+    fn dx(&self, time: F) -> F {
+        if self.in_second(time) {
+            self.second.dx(time - self.switch_time)
+        } else {
+            self.first.dx(time)
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        if self.in_second(time) {
+            self.second.ddx(time - self.switch_time)
+        } else {
+            self.first.ddx(time)
+        }
+    }
+    fn is_done(&self, time: F) -> bool {
+        if self.in_second(time) {
+            self.second.is_done(time - self.switch_time)
+        } else {
+            self.first.is_done(time)
+        }
+    }
+    fn settle_time(&self) -> F {
+        if self.switch_time.is_finite() {
+            self.switch_time + self.second.settle_time()
+        } else {
+            self.first.settle_time()
+        }
+    }
+    fn final_position(&self) -> F {
+        if self.switch_time.is_finite() {
+            self.second.final_position()
+        } else {
+            self.first.final_position()
+        }
+    }
+}
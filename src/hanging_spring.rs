@@ -0,0 +1,118 @@
+use crate::spring::Crossings;
+use crate::{Float, Resume, Simulation, Spring, SpringLimit, Tolerance};
+
+/// a spring with a constant force acting on it as well, such as a dialog hanging from a spring under gravity.
+///
+/// The force stretches the spring, so it comes to rest at an equilibrium `force / spring_constant` (the sag)
+/// away from its end point, rather than at the end point. Around the equilibrium it moves just like a
+/// `Spring`, so it can be underdamped, critically damped or overdamped in the same way.
+#[derive(Clone, Copy)]
+pub struct HangingSpring<F = f32> {
+    spring: Spring<F>, // with its end at the equilibrium.
+    end: F,
+    force: F,
+}
+impl<F: Float> HangingSpring<F> {
+    /// Create a new hanging spring with the given mass, spring constant and damping values, and a constant
+    /// force (in the direction of increasing position).
+    ///
+    /// The spring starts out "snapped" to 0.0.
+    pub fn new(mass: F, spring_constant: F, damping: F, force: F) -> HangingSpring<F> {
+        HangingSpring {
+            spring: Spring::with_constants(mass, spring_constant, damping),
+            end: F::ZERO,
+            force,
+        }
+    }
+    /// Create a new hanging spring under gravity, where the force is the mass multiplied by the given
+    /// acceleration.
+    pub fn with_gravity(
+        mass: F,
+        spring_constant: F,
+        damping: F,
+        acceleration: F,
+    ) -> HangingSpring<F> {
+        HangingSpring::new(mass, spring_constant, damping, mass * acceleration)
+    }
+    /// Set the spring's end point to the given position and velocity. If time is non-zero then the
+    /// velocity of the spring at that time (before these new values are applied) is also included, as with
+    /// `Spring::set`.
+    pub fn set(&mut self, x: F, velocity: F, time: F) {
+        self.end = x;
+        self.spring.set(self.equilibrium(), velocity, time);
+    }
+    /// Change the force, with the spring carrying on from where it is at the given time.
+    pub fn set_force(&mut self, force: F, time: F) {
+        self.force = force;
+        self.spring.set(self.equilibrium(), F::ZERO, time);
+    }
+    /// "Snap" the spring so that it hangs at rest from the given end point.
+    pub fn snap(&mut self, x: F) {
+        self.end = x;
+        self.spring.snap(self.equilibrium());
+    }
+    /// Set the tolerance used to decide when the spring is done.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.spring.set_tolerance(tolerance);
+    }
+    /// Set a limit on how much the spring can oscillate around its equilibrium.
+    pub fn set_limit(&mut self, limit: SpringLimit) {
+        self.spring.set_limit(limit);
+    }
+    /// Return the spring's end point.
+    pub fn end(&self) -> F {
+        self.end
+    }
+    /// Return the constant force.
+    pub fn force(&self) -> F {
+        self.force
+    }
+    /// Return how far the force stretches the spring past its end point.
+    pub fn sag(&self) -> F {
+        self.force / self.spring.spring_constant()
+    }
+    /// Return the position at which the spring comes to rest.
+    pub fn equilibrium(&self) -> F {
+        self.end + self.sag()
+    }
+    /// Return the spring which moves around the equilibrium (which is its end point). Its position is the
+    /// same as the hanging spring's.
+    pub fn spring(&self) -> &Spring<F> {
+        &self.spring
+    }
+    /// Return the first time (in seconds) at which the spring reaches position `p`, or NaN if it never does.
+    pub fn time_for_position(&self, p: F) -> F {
+        self.spring.time_for_position(p)
+    }
+    /// Return an iterator over every time (in seconds, in order) at which the spring passes through
+    /// position `p`.
+    pub fn crossings(&self, p: F) -> Crossings<F> {
+        self.spring.crossings(p)
+    }
+}
+impl<F: Float> Simulation<F> for HangingSpring<F> {
+    fn x(&self, time: F) -> F {
+        self.spring.x(time)
+    }
+    fn dx(&self, time: F) -> F {
+        self.spring.dx(time)
+    }
+    fn ddx(&self, time: F) -> F {
+        self.spring.ddx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.spring.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.spring.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.spring.final_position()
+    }
+}
+impl<F: Float> Resume<F> for HangingSpring<F> {
+    /// Start the spring from position `x` with velocity `v`, keeping the current end point and force.
+    fn resume(&mut self, x: F, v: F) {
+        self.spring.resume(x, v);
+    }
+}
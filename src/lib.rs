@@ -6,16 +6,67 @@
 //! Normally you would compute these in response to a touch gesture ending. All of the simulations are parametric over
 //! time and have been algebraically integrated (rather than using a numerical integration method at runtime). The advantage
 //! of algebraic integration is lower CPU overhead, and no odd behavior if frames are dropped.
+//!
+//! Every simulation is generic over its floating point type (see `Float`), and uses `f32` unless told otherwise.
+//! Use `f64` (for example `Spring::<f64>::with_constants(1.0, 90.0, 20.0)`) when animations run for a long time or values
+//! get very large. The `new` constructors of `Spring`, `Friction`, `Gravity`, `Scroll` and `Pager` always make `f32`
+//! simulations, so that code written before they were generic keeps working; each has a generic constructor as well.
+//!
+//! Gravitas supports `no_std` targets: disable the default `std` feature and enable the `libm` feature, which supplies
+//! the math functions that would otherwise come from the standard library. `Pager::new` allocates, so it needs the
+//! `alloc` feature (which `std` enables); without it, give `Pager` its snap points in caller-provided storage with
+//! `Pager::with_snap_points`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("gravitas needs either the `std` or the `libm` feature for its math functions");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod bounce;
+mod carousel;
+mod combinators;
+mod deceleration;
+mod drag_gravity;
+mod fit;
+mod fling;
+mod float;
+mod follower;
 mod friction;
 mod gravity;
+mod handoff;
+mod hanging_spring;
+mod moving_spring;
 mod pager;
+mod rubber_band;
 mod scroll;
+mod search;
 mod simulation;
+mod spline_fling;
 mod spring;
+mod spring_spec;
+mod tolerance;
 
+pub use bounce::Bounce;
+pub use carousel::Carousel;
+pub use combinators::{Clamp, Delay, Map, Offset, Scale, TimeScale};
+pub use deceleration::Deceleration;
+pub use drag_gravity::DragGravity;
+pub use fit::{FrictionFit, SpringFit};
+pub use fling::Fling;
+pub use float::Float;
+pub use follower::Follower;
 pub use friction::Friction;
 pub use gravity::Gravity;
+pub use handoff::{Handoff, Resume, Trigger};
+pub use hanging_spring::HangingSpring;
+pub use moving_spring::MovingSpring;
 pub use pager::{Pager, SnapPoint as PagerSnapPoint, SnapQuery as PagerSnapQuery};
-pub use scroll::Scroll;
+pub use rubber_band::RubberBand;
+pub use scroll::{Convention as ScrollConvention, Scroll};
 pub use simulation::Simulation;
-pub use spring::Spring;
+pub use spline_fling::SplineFling;
+pub use spring::{Crossings as SpringCrossings, Events as SpringEvents, Spring, SpringLimit};
+pub use spring_spec::{SpringMetrics, SpringSpec};
+pub use tolerance::Tolerance;
@@ -0,0 +1,109 @@
+use crate::{Float, Resume, Simulation, Spring, Tolerance};
+
+/// a spring whose end point moves at a constant velocity, such as an element following a finger.
+///
+/// Rather than calling `Spring::set` every frame with the new end point, give the end point's velocity
+/// to `set` and the spring will keep following it. As with calling `set` every frame, the damping acts on the
+/// spring's own velocity, so the spring settles into following the end at a constant distance behind it (the
+/// lag, `damping * end_velocity / spring_constant`). The difference from that is an ordinary spring, so this
+/// still has a closed form.
+#[derive(Clone, Copy)]
+pub struct MovingSpring<F = f32> {
+    transient: Spring<F>, // the difference from following at the lag, with an end of zero.
+    end: F,               // end position at start_time.
+    end_velocity: F,
+    start_time: F,
+}
+impl<F: Float> MovingSpring<F> {
+    /// Create a new moving spring with the given mass, spring constant and damping values.
+    ///
+    /// The spring starts out "snapped" to 0.0 with an end point that isn't moving.
+    pub fn new(mass: F, spring_constant: F, damping: F) -> MovingSpring<F> {
+        MovingSpring {
+            transient: Spring::with_constants(mass, spring_constant, damping),
+            end: F::ZERO,
+            end_velocity: F::ZERO,
+            start_time: F::ZERO,
+        }
+    }
+    /// Set the tolerance used to decide when the spring has caught up with its end point.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.transient.set_tolerance(tolerance);
+    }
+    /// Move the end point to `end` at the given time, from where it moves with velocity `end_velocity`. The
+    /// spring carries on from its position and velocity at that time.
+    pub fn set(&mut self, end: F, end_velocity: F, time: F) {
+        let (x, v) = (self.x(time), self.dx(time));
+        self.end = end;
+        self.end_velocity = end_velocity;
+        self.start_time = time;
+        self.start_from(x, v);
+    }
+    /// "Snap" the spring and its end point to the given value, with no velocity.
+    pub fn snap(&mut self, x: F) {
+        self.end = x;
+        self.end_velocity = F::ZERO;
+        self.start_time = F::ZERO;
+        self.transient.snap(F::ZERO);
+    }
+    /// Return the position of the end point at the given time.
+    pub fn end(&self, time: F) -> F {
+        self.end + self.end_velocity * (time - self.start_time)
+    }
+    /// Return the velocity of the end point.
+    pub fn end_velocity(&self) -> F {
+        self.end_velocity
+    }
+    /// Return how far behind its end point the spring follows once it has caught up.
+    pub fn lag(&self) -> F {
+        self.transient.damping() * self.end_velocity / self.transient.spring_constant()
+    }
+    /// Return the time after which the spring is following its end point (at the lag) to within the
+    /// tolerance. If the end point isn't moving this is the same as `settle_time`.
+    pub fn catch_up_time(&self) -> F {
+        self.transient.settle_time() + self.start_time
+    }
+    /// Start the transient from position `x` and velocity `v` at start_time.
+    fn start_from(&mut self, x: F, v: F) {
+        self.transient.snap(x - self.end + self.lag());
+        self.transient.set(F::ZERO, v - self.end_velocity, F::ZERO);
+    }
+}
+impl<F: Float> Simulation<F> for MovingSpring<F> {
+    fn x(&self, time: F) -> F {
+        self.end(time) - self.lag() + self.transient.x(time - self.start_time)
+    }
+    fn dx(&self, time: F) -> F {
+        self.end_velocity + self.transient.dx(time - self.start_time)
+    }
+    fn ddx(&self, time: F) -> F {
+        self.transient.ddx(time - self.start_time)
+    }
+    /// A spring with a moving end point is never done, even once it has caught up.
+    fn is_done(&self, time: F) -> bool {
+        self.end_velocity == F::ZERO && self.transient.is_done(time - self.start_time)
+    }
+    fn settle_time(&self) -> F {
+        if self.end_velocity == F::ZERO {
+            self.catch_up_time()
+        } else {
+            F::NAN
+        }
+    }
+    fn final_position(&self) -> F {
+        if self.end_velocity == F::ZERO {
+            self.end
+        } else {
+            F::NAN
+        }
+    }
+}
+impl<F: Float> Resume<F> for MovingSpring<F> {
+    /// Start the spring from position `x` with velocity `v`, with the end point carrying on from where it
+    /// is at time zero.
+    fn resume(&mut self, x: F, v: F) {
+        self.end = self.end(F::ZERO);
+        self.start_time = F::ZERO;
+        self.start_from(x, v);
+    }
+}
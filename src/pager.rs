@@ -1,12 +1,16 @@
-use crate::{Friction, Simulation, Spring};
+use crate::{
+    Fling, Float, Friction, Handoff, Resume, RubberBand, Simulation, Spring, SpringLimit,
+    Tolerance, Trigger,
+};
+#[cfg(feature = "alloc")]
 use core::cmp::Ordering;
 
 /// A SnapPoint is either an end point or a point of attraction. Every pager needs at least two
 /// snap points to define the extents
 #[derive(Clone, Copy, Debug)]
-pub struct SnapPoint {
+pub struct SnapPoint<F = f32> {
     /// The location of the snap point.
-    pub value: f32,
+    pub value: F,
     /// Whether we should snap at this snap point. We will snap to one point or the other if
     /// both have this value set. Otherwise we allow free movement between a point with snap
     /// set to false and a point with snap set to true.
@@ -14,40 +18,91 @@ pub struct SnapPoint {
 }
 
 /// Any number can be either between two snap points, or beyond one of the extents.
-pub enum SnapQuery {
+pub enum SnapQuery<F = f32> {
     /// The queried value lies between these two snap points.
-    Between(SnapPoint, SnapPoint),
+    Between(SnapPoint<F>, SnapPoint<F>),
     /// The queried value lies beyond this snap point.
-    Beyond(SnapPoint),
+    Beyond(SnapPoint<F>),
 }
 
+/// The storage `Pager` keeps its snap points in, unless other storage is given to `Pager::with_snap_points`.
+#[cfg(feature = "alloc")]
+type SnapPoints<F> = alloc::vec::Vec<SnapPoint<F>>;
+
+/// Pager is similar to `Scroll`, except it contains user supplied snap points which the simulation will be attracted to.
+/// These snap points are supplied to the constructor.
+///
+/// The snap points are kept in `S`, which is a `Vec` when using `Pager::new`. Any other storage which can be viewed
+/// as a slice, such as an array or a borrowed slice, can be used with `Pager::with_snap_points` to avoid allocating.
+///
+/// The pager is slowed down by `D`, which is `Friction` unless another `Fling` is given to `with_fling`, and
+/// a `Handoff` switches to a spring to bounce off of the extents or to snap.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct Pager<F = f32, S = SnapPoints<F>, D = Friction<F>> {
+    snap_points: S,
+    motion: Handoff<D, Spring<F>, F>, // the fling, handing off to a spring.
+    land_on_snap_points: bool,
+}
 /// Pager is similar to `Scroll`, except it contains user supplied snap points which the simulation will be attracted to.
 /// These snap points are supplied to the constructor.
+///
+/// The snap points are kept in `S`, which is any storage that can be viewed as a slice, such as an array or a borrowed
+/// slice, given to `Pager::with_snap_points`. Without the `alloc` feature there's no `Vec` to use by default, and since
+/// defaults have to come last the floating point type must be named as well.
+///
+/// The pager is slowed down by `D`, which is `Friction` unless another `Fling` is given to `with_fling`, and
+/// a `Handoff` switches to a spring to bounce off of the extents or to snap.
+#[cfg(not(feature = "alloc"))]
 #[derive(Clone)]
-pub struct Pager {
-    snap_points: Vec<SnapPoint>,
-    friction: Friction,
-    spring: Spring,
-    spring_time: f32, // when we transition into using a spring
+pub struct Pager<F, S, D = Friction<F>> {
+    snap_points: S,
+    motion: Handoff<D, Spring<F>, F>, // the fling, handing off to a spring.
+    land_on_snap_points: bool,
 }
-impl Pager {
+#[cfg(feature = "alloc")]
+impl Pager<f32> {
     /// Create a new scroll simulation which allows scrolls between 0 and the given extent.
-    pub fn new(snap_points: &[SnapPoint]) -> Pager {
-        let sort_predicate =
-            |a: &SnapPoint, b: &SnapPoint| a.value.partial_cmp(&b.value).unwrap_or(Ordering::Equal);
+    ///
+    /// This makes an `f32` simulation; use `with_snap_points` for other types.
+    pub fn new(snap_points: &[SnapPoint<f32>]) -> Pager<f32> {
+        let sort_predicate = |a: &SnapPoint<f32>, b: &SnapPoint<f32>| {
+            a.value.partial_cmp(&b.value).unwrap_or(Ordering::Equal)
+        };
         let mut snaps = snap_points.to_vec();
         snaps.sort_by(sort_predicate);
 
+        Pager::with_snap_points(snaps)
+    }
+}
+impl<F: Float, S: AsRef<[SnapPoint<F>]>> Pager<F, S> {
+    /// Create a new pager which keeps its snap points in the given storage rather than allocating, for example
+    /// an array of snap points. The snap points don't need to be sorted.
+    pub fn with_snap_points(snap_points: S) -> Pager<F, S> {
+        Pager {
+            snap_points,
+            motion: Handoff::new(
+                Friction::with_drag(F::from_f64(0.01)),
+                Spring::with_constants(F::ONE, F::from_f64(90.0), F::from_f64(20.0)),
+                Trigger::Time(F::NAN),
+            ),
+            land_on_snap_points: false,
+        }
+    }
+}
+impl<F: Float, S: AsRef<[SnapPoint<F>]>, D: Fling<F>> Pager<F, S, D> {
+    /// Use `fling` to slow the pager down in place of friction, for example a `Deceleration`. This is
+    /// normally done when creating the pager.
+    pub fn with_fling<G: Fling<F>>(self, fling: G) -> Pager<F, S, G> {
         Pager {
-            snap_points: snaps,
-            friction: Friction::new(0.01),
-            spring: Spring::new(1.0, 90.0, 20.0),
-            spring_time: std::f32::NAN,
+            snap_points: self.snap_points,
+            motion: Handoff::new(fling, *self.motion.second(), Trigger::Time(F::NAN)),
+            land_on_snap_points: self.land_on_snap_points,
         }
     }
     /// Start a gesture-based scroll from the scroll position `x` with velocity `v`.
-    pub fn set(&mut self, x: f32, v: f32) {
-        self.friction.set(x, v);
+    pub fn set(&mut self, x: F, v: F) {
+        self.motion.first_mut().resume(x, v);
         // We need to find the snap points that we're between. If we're beyond an extent then we
         // will spring back to the extent. Otherwise we will either spring or snap depending on
         // the setup and our velocity.
@@ -61,22 +116,18 @@ impl Pager {
             SnapQuery::Beyond(SnapPoint { value, snap: false }) => {
                 // If our velocity will take us beyond the snap point, then just use that to get back,
                 // otherwise we need to spring.
-                let time_to_extent = self.friction.time_for_position(value);
-                if time_to_extent.is_finite() && time_to_extent > 0.0 {
+                let time_to_extent = self.motion.first().time_for_position(value);
+                if time_to_extent.is_finite() && time_to_extent > F::ZERO {
                     // Yep, friction will bring us back in bounds.
-                    self.spring_time = std::f32::NAN;
+                    self.motion.set_trigger(Trigger::Time(F::NAN));
                 } else {
                     // Oh, looks like we need to spring.
-                    self.spring_time = 0.0;
-                    self.spring.snap(x);
-                    self.spring.set(value, v, 0.0);
+                    self.spring_to(value, F::ZERO);
                 }
             }
             SnapQuery::Beyond(SnapPoint { value, snap: true }) => {
                 // Don't use friction here, just bounce to the point.
-                self.spring_time = 0.0;
-                self.spring.snap(x);
-                self.spring.set(value, v, 0.0);
+                self.spring_to(value, F::ZERO);
             }
             SnapQuery::Between(
                 SnapPoint {
@@ -89,44 +140,47 @@ impl Pager {
                 },
             ) => {
                 // We're between two points that snap so we've got to pick one of them and then snap to it.
-                let end_point = self.friction.x(10000.0);
+                let end_point = self.motion.first().final_position();
                 let a_dist = (a - end_point).abs();
                 let b_dist = (b - end_point).abs();
                 let snap_target = if a_dist < b_dist { a } else { b };
-                self.spring_time = 0.0;
-                self.spring.snap(x);
-                self.spring.set(snap_target, v, 0.0);
+                if self.land_on_snap_points {
+                    // Change the velocity a little so that the fling stops right on the snap point, as
+                    // long as that doesn't mean turning around.
+                    let landing_v = self.motion.first().velocity_for_distance(snap_target - x);
+                    if landing_v * v > F::ZERO {
+                        self.motion.first_mut().resume(x, landing_v);
+                        self.motion.set_trigger(Trigger::Time(F::NAN));
+                        return;
+                    }
+                }
+                self.spring_to(snap_target, F::ZERO);
             }
             SnapQuery::Between(SnapPoint { value: a, .. }, SnapPoint { value: b, .. }) => {
                 // We're between two points, but both of them do not snap, so we're going to do a regular
                 // scroll. So let friction do its thing until/unless we hit one of the snap points, in
                 // which case do a bounce.
-                let time_to_a = self.friction.time_for_position(a);
-                let time_to_b = self.friction.time_for_position(b);
-                if time_to_a.is_finite() && time_to_a > 0.0 {
-                    self.spring_time = time_to_a;
-                    self.spring.snap(a);
-                    self.spring
-                        .set(a, self.friction.dx(self.spring_time), self.spring_time);
-                } else if time_to_b.is_finite() && time_to_b > 0.0 {
-                    self.spring_time = time_to_b;
-                    self.spring.snap(b);
-                    self.spring
-                        .set(b, self.friction.dx(self.spring_time), self.spring_time);
+                let time_to_a = self.motion.first().time_for_position(a);
+                let time_to_b = self.motion.first().time_for_position(b);
+                if time_to_a.is_finite() && time_to_a > F::ZERO {
+                    self.spring_to(a, time_to_a);
+                } else if time_to_b.is_finite() && time_to_b > F::ZERO {
+                    self.spring_to(b, time_to_b);
                 } else {
-                    self.spring_time = std::f32::NAN;
+                    self.motion.set_trigger(Trigger::Time(F::NAN));
                 }
             }
         }
     }
 
     /// Figure out which snap points the given position is between. This can be used by external callers
-    /// to determine if they are in an "overdrag" case where they should damp movement or not.
-    pub fn query(&self, x: f32) -> SnapQuery {
-        let mut less_than: Option<SnapPoint> = None;
-        let mut greater_than: Option<SnapPoint> = None;
-        // This could be optimized since the snap points are sorted.
-        self.snap_points.iter().for_each(|snap| {
+    /// to determine if they are in an "overdrag" case where they should damp movement or not, which
+    /// `rubber_band` does.
+    pub fn query(&self, x: F) -> SnapQuery<F> {
+        let mut less_than: Option<SnapPoint<F>> = None;
+        let mut greater_than: Option<SnapPoint<F>> = None;
+        // This could be optimized if the snap points were sorted.
+        self.snap_points.as_ref().iter().for_each(|snap| {
             // Find the smallest value that's greater than "x".
             if snap.value > x {
                 greater_than = match greater_than {
@@ -161,46 +215,99 @@ impl Pager {
             // but if it does happen then invent an extent at zero that we can bounce
             // back to.
             (None, None) => SnapQuery::Beyond(SnapPoint {
-                value: 0.0,
+                value: F::ZERO,
                 snap: true,
             }),
         }
     }
 
     /// Jump to a position with an animation.
-    pub fn jump_to(&mut self, position: f32, time: f32) {
+    pub fn jump_to(&mut self, position: F, time: F) {
         let x = self.x(time);
         let dx = self.dx(time);
 
-        self.spring_time = 0.0;
-        self.spring.snap(x);
-        self.spring.set(position, dx, 0.0);
+        self.motion.first_mut().resume(x, dx);
+        self.spring_to(position, F::ZERO);
+    }
+    /// Hand off from the fling to a spring heading for `end` at `time`, or never if it isn't finite.
+    fn spring_to(&mut self, end: F, time: F) {
+        self.motion.second_mut().snap(end);
+        self.motion.set_trigger(Trigger::Time(time));
     }
 
-    fn in_spring(&self, time: f32) -> bool {
-        self.spring_time.is_finite() && time >= self.spring_time
+    /// Return the first time (in seconds, from zero) at which the scroll position reaches `p`, or NaN if
+    /// it never does. This looks at the fling first, and then at the spring (including any bounces).
+    pub fn time_for_position(&self, p: F) -> F {
+        self.motion.time_for_position(p)
     }
-}
-impl Simulation for Pager {
-    fn x(&self, time: f32) -> f32 {
-        if self.in_spring(time) {
-            self.spring.x(time)
-        } else {
-            self.friction.x(time)
-        }
+    /// Set the tolerance used to decide when the scroll is done. This is given to both the fling and the
+    /// spring, so the velocity tolerance also decides when the fling stops.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.motion.first_mut().set_tolerance(tolerance);
+        self.motion.second_mut().set_tolerance(tolerance);
     }
-    fn dx(&self, time: f32) -> f32 {
-        if self.in_spring(time) {
-            self.spring.dx(time)
-        } else {
-            self.friction.dx(time)
-        }
+    /// Set a limit on how much the spring used for the extents and snap points can oscillate, for example
+    /// to stop it from going past the snap point it's heading to.
+    pub fn set_spring_limit(&mut self, limit: SpringLimit) {
+        self.motion.second_mut().set_limit(limit);
+    }
+    /// Choose whether a fling between two snap points has its velocity adjusted so that it stops exactly on
+    /// the snap point it's heading for, rather than springing to it. The velocity is only adjusted when the
+    /// fling is already heading towards that snap point; otherwise the spring is used.
+    pub fn set_land_on_snap_points(&mut self, land: bool) {
+        self.land_on_snap_points = land;
+    }
+    /// Return the content position for a finger dragged to `offset`, which moves freely between the first and last snap points
+    /// and is damped by `band` beyond them.
+    pub fn rubber_band(&self, band: &RubberBand<F>, offset: F) -> F {
+        let (lower, upper) = self.extents();
+        band.position(lower, upper, offset)
+    }
+    /// Return the finger offset which drags the content to `position`, which is the inverse of `rubber_band`.
+    /// This is useful when a drag starts while the content is still beyond the first and last snap points.
+    pub fn rubber_band_offset(&self, band: &RubberBand<F>, position: F) -> F {
+        let (lower, upper) = self.extents();
+        band.offset(lower, upper, position)
     }
-    fn is_done(&self, time: f32) -> bool {
-        if self.in_spring(time) {
-            self.spring.is_done(time)
-        } else {
-            self.friction.is_done(time)
+    /// Return the velocity of the content when the finger is at `offset` and moving with `velocity`, which is
+    /// what to give to `set` when the drag ends.
+    pub fn rubber_band_velocity(&self, band: &RubberBand<F>, offset: F, velocity: F) -> F {
+        let (lower, upper) = self.extents();
+        band.velocity(lower, upper, offset, velocity)
+    }
+    fn extents(&self) -> (F, F) {
+        let mut points = self.snap_points.as_ref().iter().map(|snap| snap.value);
+        match points.next() {
+            Some(first) => points.fold((first, first), |(lower, upper), value| {
+                (lower.min(value), upper.max(value))
+            }),
+            // As with `query`, invent an extent at zero.
+            None => (F::ZERO, F::ZERO),
         }
     }
 }
+impl<F: Float, S: AsRef<[SnapPoint<F>]>, D: Fling<F>> Simulation<F> for Pager<F, S, D> {
+    fn x(&self, time: F) -> F {
+        self.motion.x(time)
+    }
+    fn dx(&self, time: F) -> F {
+        self.motion.dx(time)
+    }
+    fn ddx(&self, time: F) -> F {
+        self.motion.ddx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.motion.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.motion.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.motion.final_position()
+    }
+}
+impl<F: Float, S: AsRef<[SnapPoint<F>]>, D: Fling<F>> Resume<F> for Pager<F, S, D> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
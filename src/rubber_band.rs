@@ -0,0 +1,123 @@
+use crate::Float;
+
+/// a curve which damps movement beyond the ends of a `Scroll` or `Pager` while it's being dragged, so that the
+/// content moves less than the finger.
+///
+/// Each curve maps how far the finger has dragged past an end (the overdrag) to how far the content moves past
+/// it, and back again. The curves are symmetric, so dragging past either end works the same way. Use
+/// `Scroll::rubber_band` and `Pager::rubber_band` to map positions during a drag, and
+/// `Scroll::rubber_band_velocity` and `Pager::rubber_band_velocity` to find the velocity to give to `set` when
+/// the drag ends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RubberBand<F = f32> {
+    /// The curve used by `UIScrollView`, `(1 - 1 / (x * coefficient / dimension + 1)) * dimension`, which
+    /// never moves further than `dimension` (normally the size of the view). UIKit uses a coefficient of 0.55.
+    Ios {
+        /// How stiff the band is; the content moves this much for each unit of overdrag at first.
+        coefficient: F,
+        /// How far the content can move past the end, at most.
+        dimension: F,
+    },
+    /// The content moves a constant fraction of the overdrag.
+    Linear {
+        /// The fraction of the overdrag which the content moves.
+        factor: F,
+    },
+    /// The content moves `scale * ln(1 + x / scale)`, which follows the finger at first and then slows down,
+    /// but keeps moving however far it's dragged.
+    Log {
+        /// How far the content moves before it is noticeably damped.
+        scale: F,
+    },
+}
+impl<F: Float> RubberBand<F> {
+    /// Create UIKit's rubber band, with a coefficient of 0.55, for a view of the given size.
+    pub fn ios(dimension: F) -> RubberBand<F> {
+        RubberBand::Ios {
+            coefficient: F::from_f64(0.55),
+            dimension,
+        }
+    }
+    /// Return how far the content moves for an overdrag of `x`.
+    pub fn damp(&self, x: F) -> F {
+        if x < F::ZERO {
+            return -self.damp(-x);
+        }
+        match *self {
+            RubberBand::Ios {
+                coefficient,
+                dimension,
+            } => (F::ONE - F::ONE / (x * coefficient / dimension + F::ONE)) * dimension,
+            RubberBand::Linear { factor } => x * factor,
+            RubberBand::Log { scale } => scale * (F::ONE + x / scale).ln(),
+        }
+    }
+    /// Return the overdrag which moves the content by `y`. This is the inverse of `damp`, and is infinite
+    /// if the content can't move that far.
+    pub fn undamp(&self, y: F) -> F {
+        if y < F::ZERO {
+            return -self.undamp(-y);
+        }
+        match *self {
+            RubberBand::Ios {
+                coefficient,
+                dimension,
+            } => {
+                if y >= dimension {
+                    F::INFINITY
+                } else {
+                    y / (coefficient * (F::ONE - y / dimension))
+                }
+            }
+            RubberBand::Linear { factor } => y / factor,
+            RubberBand::Log { scale } => scale * ((y / scale).exp() - F::ONE),
+        }
+    }
+    /// Return how fast the content moves compared to the finger at an overdrag of `x`, which is the
+    /// derivative of `damp`.
+    pub fn slope(&self, x: F) -> F {
+        let x = x.abs();
+        match *self {
+            RubberBand::Ios {
+                coefficient,
+                dimension,
+            } => {
+                let s = x * coefficient / dimension + F::ONE;
+                coefficient / (s * s)
+            }
+            RubberBand::Linear { factor } => factor,
+            RubberBand::Log { scale } => F::ONE / (F::ONE + x / scale),
+        }
+    }
+    /// Return the content position for a finger at `offset`, where the content can move freely between
+    /// `lower` and `upper` and is damped beyond them.
+    pub fn position(&self, lower: F, upper: F, offset: F) -> F {
+        if offset > upper {
+            upper + self.damp(offset - upper)
+        } else if offset < lower {
+            lower + self.damp(offset - lower)
+        } else {
+            offset
+        }
+    }
+    /// Return the finger offset which puts the content at `position`. This is the inverse of `position`.
+    pub fn offset(&self, lower: F, upper: F, position: F) -> F {
+        if position > upper {
+            upper + self.undamp(position - upper)
+        } else if position < lower {
+            lower + self.undamp(position - lower)
+        } else {
+            position
+        }
+    }
+    /// Return the velocity of the content when the finger is at `offset` moving with `velocity`.
+    pub fn velocity(&self, lower: F, upper: F, offset: F, velocity: F) -> F {
+        if offset > upper {
+            velocity * self.slope(offset - upper)
+        } else if offset < lower {
+            velocity * self.slope(offset - lower)
+        } else {
+            velocity
+        }
+    }
+}
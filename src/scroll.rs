@@ -1,88 +1,193 @@
-use crate::{Friction, Simulation, Spring};
+use crate::{
+    Fling, Float, Friction, Handoff, Resume, RubberBand, Simulation, Spring, SpringLimit,
+    Tolerance, Trigger,
+};
 
 /// a combination of friction and springs to create a touch-driven scrolling simulation.
 ///
 /// <a href="https://cdn.rawgit.com/iamralpht/gravitas.js/master/examples/Scrolling/index.html">Gravitas JavaScript example of the scrolling simulation.</a>
+///
+/// The scroll is slowed down by `D`, which is `Friction` unless another `Fling` is given to `with_fling`, and
+/// a `Handoff` switches to a spring if it reaches either end.
+///
+/// The scroll range is from `min` to `max`, and the positions are either in that range or its negation (see
+/// `Convention`). `Scroll::new` uses the range from 0 to the extent with negative positions, so it scrolls
+/// between `-extent` and 0.
 #[derive(Clone, Copy)]
-pub struct Scroll {
-    extent: f32,
-    friction: Friction,
-    spring: Spring,
-    spring_time: f32, // when we transition into using a spring
+pub struct Scroll<F = f32, D = Friction<F>> {
+    min: F,
+    max: F,
+    convention: Convention,
+    motion: Handoff<D, Spring<F>, F>, // the fling, handing off to a spring at the ends.
+}
+/// Whether the positions of a `Scroll` are its scroll offsets, or their negation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Convention {
+    /// Positions are scroll offsets, from `min` to `max`, which increase as the content scrolls forward.
+    Positive,
+    /// Positions are the negation of the scroll offsets, from `-max` to `-min`, such as a translation
+    /// applied to the content. This is what `Scroll::new` uses.
+    Negative,
 }
-impl Scroll {
+
+impl Scroll<f32> {
     /// Create a new scroll simulation which allows scrolls between 0 and the given extent.
-    pub fn new(extent: f32) -> Scroll {
+    ///
+    /// Positions are negative (see `Convention::Negative`), so the scroll position goes from 0 to `-extent`.
+    /// This makes an `f32` simulation; use `with_extent` for other types.
+    pub fn new(extent: f32) -> Scroll<f32> {
+        Scroll::with_extent(extent)
+    }
+}
+impl<F: Float> Scroll<F> {
+    /// Create a new scroll simulation which allows scrolls between 0 and the given extent, like `new` but for
+    /// any `Float` type.
+    pub fn with_extent(extent: F) -> Scroll<F> {
+        Scroll::with_range(F::ZERO, extent).with_convention(Convention::Negative)
+    }
+    /// Create a new scroll simulation which allows scrolls between `min` and `max`, such as a range that
+    /// starts at a content inset.
+    ///
+    /// Positions are scroll offsets (see `Convention::Positive`), so the scroll position goes from `min` to
+    /// `max`.
+    pub fn with_range(min: F, max: F) -> Scroll<F> {
         Scroll {
-            extent,
-            friction: Friction::new(0.01),
-            spring: Spring::new(1.0, 90.0, 20.0),
-            spring_time: std::f32::NAN,
+            min,
+            max,
+            convention: Convention::Positive,
+            motion: Handoff::new(
+                Friction::with_drag(F::from_f64(0.01)),
+                Spring::with_constants(F::ONE, F::from_f64(90.0), F::from_f64(20.0)),
+                Trigger::Time(F::NAN),
+            ),
         }
     }
+}
+impl<F: Float, D: Fling<F>> Scroll<F, D> {
+    /// Use `fling` to slow the scroll down in place of friction, for example a `Deceleration`. This is
+    /// normally done when creating the scroll, as in `Scroll::new(1000.0).with_fling(Deceleration::normal())`.
+    pub fn with_fling<G: Fling<F>>(self, fling: G) -> Scroll<F, G> {
+        Scroll {
+            min: self.min,
+            max: self.max,
+            convention: self.convention,
+            motion: Handoff::new(fling, *self.motion.second(), Trigger::Time(F::NAN)),
+        }
+    }
+    /// Use the given sign convention for positions. This is normally done when creating the scroll, as in
+    /// `Scroll::with_range(0.0, 1000.0).with_convention(ScrollConvention::Negative)`.
+    pub fn with_convention(mut self, convention: Convention) -> Scroll<F, D> {
+        self.convention = convention;
+        self
+    }
     /// Start a gesture-based scroll from the scroll position `x` with velocity `v`.
-    pub fn set(&mut self, x: f32, v: f32) {
-        self.friction.set(x, v);
+    pub fn set(&mut self, x: F, v: F) {
+        self.motion.first_mut().resume(x, v);
+        let (lower, upper) = self.bounds();
         // If we're already into overscroll on either end then just start out in the spring. If
         // friction with our velocity is going to take us out of overscroll then we don't bother
         // with the spring.
-        let time_to_zero = self.friction.time_for_position(0.0);
-        let time_to_extent = self.friction.time_for_position(-self.extent);
-        if x > 0.0 && (!time_to_zero.is_finite() || time_to_zero < 0.0) {
-            self.spring_time = 0.0;
-            self.spring.snap(x);
-            self.spring.set(0.0, v, 0.0);
-        } else if x < -self.extent && (!time_to_extent.is_finite() || time_to_extent < 0.0) {
-            self.spring_time = 0.0;
-            self.spring.snap(x);
-            self.spring.set(-self.extent, v, 0.0);
+        let time_to_upper = self.motion.first().time_for_position(upper);
+        let time_to_lower = self.motion.first().time_for_position(lower);
+        if x > upper && (!time_to_upper.is_finite() || time_to_upper < F::ZERO) {
+            self.spring_to(upper, F::ZERO);
+        } else if x < lower && (!time_to_lower.is_finite() || time_to_lower < F::ZERO) {
+            self.spring_to(lower, F::ZERO);
+        } else if v >= F::ZERO {
+            // Figure out which extent we're heading towards, and hand off to the spring when we get
+            // there.
+            self.spring_to(upper, time_to_upper);
         } else {
-            // Figure out which extent we're heading towards and then calculate the time
-            // we'll transition into the spring.
-            if v >= 0.0 {
-                self.spring.snap(0.0);
-                self.spring_time = time_to_zero;
-                self.spring
-                    .set(0.0, self.friction.dx(self.spring_time), self.spring_time);
-            } else {
-                self.spring.snap(-self.extent);
-                self.spring_time = time_to_extent;
-                self.spring.set(
-                    -self.extent,
-                    self.friction.dx(self.spring_time),
-                    self.spring_time,
-                );
-            }
+            self.spring_to(lower, time_to_lower);
         }
     }
-    /// Return the maximum extent which can be scrolled to.
-    pub fn extent(&self) -> f32 {
-        self.extent
+    /// Hand off from the fling to a spring heading for `end` at `time`, or never if it isn't finite.
+    fn spring_to(&mut self, end: F, time: F) {
+        self.motion.second_mut().snap(end);
+        self.motion.set_trigger(Trigger::Time(time));
     }
-    fn in_spring(&self, time: f32) -> bool {
-        self.spring_time.is_finite() && time >= self.spring_time
+    /// Set the range which can be scrolled, from `min` to `max`. This applies from the next `set`.
+    pub fn set_range(&mut self, min: F, max: F) {
+        self.min = min;
+        self.max = max;
     }
-}
-impl Simulation for Scroll {
-    fn x(&self, time: f32) -> f32 {
-        if self.in_spring(time) {
-            self.spring.x(time)
-        } else {
-            self.friction.x(time)
-        }
+    /// Return the range which can be scrolled, as given to `with_range` or `set_range`.
+    pub fn range(&self) -> (F, F) {
+        (self.min, self.max)
     }
-    fn dx(&self, time: f32) -> f32 {
-        if self.in_spring(time) {
-            self.spring.dx(time)
-        } else {
-            self.friction.dx(time)
-        }
+    /// Return the sign convention used for positions.
+    pub fn convention(&self) -> Convention {
+        self.convention
     }
-    fn is_done(&self, time: f32) -> bool {
-        if self.in_spring(time) {
-            self.spring.is_done(time)
-        } else {
-            self.friction.is_done(time)
+    /// Return the lowest and highest positions which the scroll comes to rest between, using its sign
+    /// convention. These are `(-max, -min)` for negative positions.
+    pub fn bounds(&self) -> (F, F) {
+        match self.convention {
+            Convention::Positive => (self.min, self.max),
+            Convention::Negative => (-self.max, -self.min),
         }
     }
+    /// Return the length of the range which can be scrolled, which is the extent given to `Scroll::new`.
+    pub fn extent(&self) -> F {
+        self.max - self.min
+    }
+    /// Return the first time (in seconds, from zero) at which the scroll position reaches `p`, or NaN if
+    /// it never does. This looks at the fling first, and then at the spring (including any bounces).
+    pub fn time_for_position(&self, p: F) -> F {
+        self.motion.time_for_position(p)
+    }
+    /// Set the tolerance used to decide when the scroll is done. This is given to both the fling and the
+    /// spring, so the velocity tolerance also decides when the fling stops.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.motion.first_mut().set_tolerance(tolerance);
+        self.motion.second_mut().set_tolerance(tolerance);
+    }
+    /// Set a limit on how much the spring used at the ends can oscillate, for example to stop it from
+    /// going past the end it's returning to.
+    pub fn set_spring_limit(&mut self, limit: SpringLimit) {
+        self.motion.second_mut().set_limit(limit);
+    }
+    /// Return the content position for a finger dragged to `offset`, which moves freely between the bounds
+    /// and is damped by `band` beyond them.
+    pub fn rubber_band(&self, band: &RubberBand<F>, offset: F) -> F {
+        let (lower, upper) = self.bounds();
+        band.position(lower, upper, offset)
+    }
+    /// Return the finger offset which drags the content to `position`, which is the inverse of `rubber_band`.
+    /// This is useful when a drag starts while the content is still beyond the bounds.
+    pub fn rubber_band_offset(&self, band: &RubberBand<F>, position: F) -> F {
+        let (lower, upper) = self.bounds();
+        band.offset(lower, upper, position)
+    }
+    /// Return the velocity of the content when the finger is at `offset` and moving with `velocity`, which is
+    /// what to give to `set` when the drag ends.
+    pub fn rubber_band_velocity(&self, band: &RubberBand<F>, offset: F, velocity: F) -> F {
+        let (lower, upper) = self.bounds();
+        band.velocity(lower, upper, offset, velocity)
+    }
+}
+impl<F: Float, D: Fling<F>> Simulation<F> for Scroll<F, D> {
+    fn x(&self, time: F) -> F {
+        self.motion.x(time)
+    }
+    fn dx(&self, time: F) -> F {
+        self.motion.dx(time)
+    }
+    fn ddx(&self, time: F) -> F {
+        self.motion.ddx(time)
+    }
+    fn is_done(&self, time: F) -> bool {
+        self.motion.is_done(time)
+    }
+    fn settle_time(&self) -> F {
+        self.motion.settle_time()
+    }
+    fn final_position(&self) -> F {
+        self.motion.final_position()
+    }
+}
+impl<F: Float, D: Fling<F>> Resume<F> for Scroll<F, D> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
 }
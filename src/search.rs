@@ -0,0 +1,149 @@
+use crate::Float;
+
+// Numerical searches over time, for the simulations (or queries) that don't have a closed form.
+
+// How far apart (in seconds) to sample when looking for an event.
+const STEP: f64 = 1.0 / 60.0;
+// Give up looking for an event after this much time (in seconds).
+const LIMIT: f64 = 600.0;
+// The most times to bisect once an event has been bracketed.
+const BISECTIONS: usize = 32;
+
+/// Return the earliest time at or after `start` at which `found` becomes true (to within `precision`
+/// seconds), or NaN if it doesn't happen before `give_up` becomes true (or the search limit is reached).
+/// `found` is sampled and then bisected, so an event that starts and finishes between two samples can
+/// be missed.
+pub(crate) fn first_time<F: Float>(
+    start: F,
+    precision: F,
+    found: impl Fn(F) -> bool,
+    give_up: impl Fn(F) -> bool,
+) -> F {
+    if found(start) {
+        return start;
+    }
+    let step = F::from_f64(STEP);
+    let limit = start + F::from_f64(LIMIT);
+    let mut before = start;
+    while before < limit {
+        let after = before + step;
+        if found(after) {
+            return bisect(before, after, precision, &found);
+        }
+        if give_up(after) {
+            break;
+        }
+        before = after;
+    }
+    F::NAN
+}
+
+/// Return the earliest time at or after `start` at which `found` becomes true (to within `precision`
+/// seconds), for a `found` which stays true once it becomes true, or NaN if it never does. Unlike
+/// `first_time` there's no limit on how far ahead to look, since the steps double in length.
+pub(crate) fn first_time_monotonic<F: Float>(
+    start: F,
+    precision: F,
+    found: impl Fn(F) -> bool,
+) -> F {
+    if found(start) {
+        return start;
+    }
+    let mut before = start;
+    let mut step = F::ONE;
+    loop {
+        let after = before + step;
+        if !after.is_finite() {
+            return F::NAN;
+        }
+        if found(after) {
+            return bisect(before, after, precision, &found);
+        }
+        before = after;
+        step = step + step;
+    }
+}
+
+/// Return the time between `before` (where `found` is false) and `after` (where it is true) at which
+/// `found` becomes true, to within `precision` seconds.
+pub(crate) fn bisect<F: Float>(
+    mut before: F,
+    mut after: F,
+    precision: F,
+    found: impl Fn(F) -> bool,
+) -> F {
+    let half = F::from_f64(0.5);
+    for _ in 0..BISECTIONS {
+        if after - before <= precision {
+            break;
+        }
+        let mid = (before + after) * half;
+        if found(mid) {
+            after = mid;
+        } else {
+            before = mid;
+        }
+    }
+    after
+}
+
+/// Return the time after which `(a + b * t) * e^(r * t)` stays below `limit`, where `a` and `b` are
+/// non-negative and `r` is negative. This is used to bound the decay of the spring solutions.
+pub(crate) fn decay_time<F: Float>(a: F, b: F, r: F, limit: F) -> F {
+    if r >= F::ZERO {
+        // No decay, so the curve only stays below the limit if it's zero.
+        return if a == F::ZERO && b == F::ZERO {
+            F::ZERO
+        } else {
+            F::NAN
+        };
+    }
+    let time = if b <= F::ZERO {
+        (limit / a).ln() / r
+    } else {
+        // Substituting s = a + b * t gives (r * s / b) * e^(r * s / b) = z, which is solved by the
+        // lower branch of the Lambert W function (we want the later of the two solutions). z can be too
+        // small to represent, so work with its logarithm.
+        let ln_z = (-r / b * limit).ln() + r * a / b;
+        if ln_z > -F::ONE {
+            // The curve never gets as high as the limit.
+            return F::ZERO;
+        }
+        let s = b * lambert_w_lower(ln_z) / r;
+        (s - a) / b
+    };
+    if time.is_finite() {
+        time.max(F::ZERO)
+    } else {
+        F::ZERO
+    }
+}
+
+/// The lower branch (W<sub>-1</sub>) of the Lambert W function, which solves `w * e^w = z` for `w <= -1`
+/// where `-1/e <= z < 0`. This takes `ln(-z)` rather than `z`, and solves `w + ln(-w) = ln(-z)`.
+pub(crate) fn lambert_w_lower<F: Float>(ln_z: F) -> F {
+    let one = F::ONE;
+    // Start from the series around the branch point, or the asymptotic expansion near zero.
+    let mut w = if ln_z > F::from_f64(-2.0) {
+        let p = -(F::from_f64(2.0) * (one - (ln_z + one).exp()))
+            .max(F::ZERO)
+            .sqrt();
+        -one + p - p * p / F::from_f64(3.0)
+    } else {
+        ln_z - (-ln_z).ln()
+    };
+    // Then refine with Newton's method.
+    for _ in 0..BISECTIONS {
+        let slope = one + one / w;
+        if slope == F::ZERO {
+            break;
+        }
+        let next = (w - (w + (-w).ln() - ln_z) / slope).min(-one);
+        let done = (next - w).abs() <= F::EPSILON * w.abs();
+        w = next;
+        if done {
+            break;
+        }
+    }
+    w
+}
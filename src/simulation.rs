@@ -1,9 +1,88 @@
+use crate::combinators::{Clamp, Delay, Map, Offset, Scale, TimeScale};
+use crate::search::first_time;
+use crate::Float;
+
 /// common methods implemented by every simulation allowing easy integration into an animation system.
-pub trait Simulation {
+///
+/// The simulations are generic over the float type used for position, velocity and time. This
+/// defaults to `f32`.
+///
+/// A simulation can be moved into another coordinate space (or time) with the adapter methods, such
+/// as `offset`, `scale` and `delay`, which wrap it in another simulation.
+pub trait Simulation<F: Float = f32> {
     /// Return the position for the given time (in seconds).
-    fn x(&self, time: f32) -> f32;
+    fn x(&self, time: F) -> F;
     /// Return the velocity for the given time (in seconds).
-    fn dx(&self, time: f32) -> f32;
+    fn dx(&self, time: F) -> F;
     /// Return true if the simulation has reached a final position at the given time (in seconds).
-    fn is_done(&self, time: f32) -> bool;
+    fn is_done(&self, time: F) -> bool;
+    /// Return the acceleration for the given time (in seconds).
+    ///
+    /// The simulations in this crate compute this analytically. The default implementation estimates it
+    /// from the velocity a millisecond either side of `time`.
+    fn ddx(&self, time: F) -> F {
+        let h = F::from_f64(0.001);
+        (self.dx(time + h) - self.dx(time - h)) / (h + h)
+    }
+    /// Return the time (in seconds) at which the simulation will be done, or NaN if it never finishes.
+    ///
+    /// The simulations in this crate compute this directly. The default implementation searches forward
+    /// in time for `is_done` to become true, and gives up (returning NaN) after ten minutes.
+    fn settle_time(&self) -> F {
+        first_time(F::ZERO, F::ZERO, |t| self.is_done(t), |_| false)
+    }
+    /// Return the position that the simulation will come to rest at, or NaN if it never finishes.
+    ///
+    /// For simulations which approach their final position over an infinite time (like `Friction`) this
+    /// is the limit, rather than the position at `settle_time`. The default implementation returns the
+    /// position at `settle_time`.
+    fn final_position(&self) -> F {
+        self.x(self.settle_time())
+    }
+
+    /// Add `offset` to the position of this simulation.
+    fn offset(self, offset: F) -> Offset<Self, F>
+    where
+        Self: Sized,
+    {
+        Offset::new(self, offset)
+    }
+    /// Multiply the position (and so the velocity) of this simulation by `scale`.
+    fn scale(self, scale: F) -> Scale<Self, F>
+    where
+        Self: Sized,
+    {
+        Scale::new(self, scale)
+    }
+    /// Start this simulation `delay` seconds later.
+    fn delay(self, delay: F) -> Delay<Self, F>
+    where
+        Self: Sized,
+    {
+        Delay::new(self, delay)
+    }
+    /// Run this simulation `factor` times faster.
+    fn time_scale(self, factor: F) -> TimeScale<Self, F>
+    where
+        Self: Sized,
+    {
+        TimeScale::new(self, factor)
+    }
+    /// Keep the position of this simulation between `min` and `max`.
+    fn clamp(self, min: F, max: F) -> Clamp<Self, F>
+    where
+        Self: Sized,
+    {
+        Clamp::new(self, min, max)
+    }
+    /// Transform the position of this simulation with `map`. The `derivative` of `map` (with respect to
+    /// position) is used to transform the velocity.
+    fn map<M, D>(self, map: M, derivative: D) -> Map<Self, M, D>
+    where
+        Self: Sized,
+        M: Fn(F) -> F,
+        D: Fn(F) -> F,
+    {
+        Map::new(self, map, derivative)
+    }
 }
@@ -0,0 +1,214 @@
+use crate::search::bisect;
+use crate::{Fling, Float, Resume, Simulation, Tolerance};
+
+// The constants from Android's OverScroller.
+const DECELERATION_RATE: f64 = 2.358_201_815_066_046; // ln(0.78) / ln(0.9)
+const INFLEXION: f64 = 0.35;
+const START_TENSION: f64 = 0.5;
+const END_TENSION: f64 = 1.0;
+const GRAVITY_EARTH: f64 = 9.80665;
+const INCHES_PER_METER: f64 = 39.37;
+const SCROLL_FRICTION: f64 = 0.015;
+
+/// a fling that follows the spline used by Android's `OverScroller`, rather than exponential decay.
+///
+/// The distance and duration of the fling come from the same formulas as `OverScroller`, based on a
+/// physical coefficient (from the screen density) and the scroll friction, and the position follows its
+/// spline curve. Android samples that curve into a table, but here it's solved directly.
+#[derive(Clone, Copy)]
+pub struct SplineFling<F = f32> {
+    x: F,
+    distance: F, // signed.
+    duration: F,
+    physical_coefficient: F,
+    friction: F,
+    tolerance: Tolerance<F>,
+}
+impl<F: Float> SplineFling<F> {
+    /// Create a new fling for a display with the given density, which is Android's `DisplayMetrics.density`
+    /// (so 1.0 is 160 pixels per inch), using the default scroll friction of 0.015.
+    pub fn new(density: F) -> SplineFling<F> {
+        let ppi = density * F::from_f64(160.0);
+        SplineFling {
+            x: F::ZERO,
+            distance: F::ZERO,
+            duration: F::ZERO,
+            physical_coefficient: F::from_f64(GRAVITY_EARTH * INCHES_PER_METER * 0.84) * ppi,
+            friction: F::from_f64(SCROLL_FRICTION),
+            tolerance: Tolerance::default(),
+        }
+    }
+    /// Set the scroll friction, as with `OverScroller::setFriction`. This applies from the next `set`.
+    pub fn set_friction(&mut self, friction: F) {
+        self.friction = friction;
+    }
+    /// Set the initial (time = 0.0) position and velocity (in pixels per second).
+    pub fn set(&mut self, x: F, v: F) {
+        self.x = x;
+        self.distance = if v < F::ZERO {
+            -self.distance(v)
+        } else {
+            self.distance(v)
+        };
+        self.duration = self.duration(v);
+    }
+    /// Return how far a fling with velocity `v` travels, from `OverScroller`'s `getSplineFlingDistance`.
+    pub fn distance(&self, v: F) -> F {
+        let rate = F::from_f64(DECELERATION_RATE);
+        self.friction
+            * self.physical_coefficient
+            * (rate / (rate - F::ONE) * self.deceleration(v)).exp()
+    }
+    /// Return how long (in seconds) a fling with velocity `v` lasts, from `OverScroller`'s
+    /// `getSplineFlingDuration`.
+    pub fn duration(&self, v: F) -> F {
+        let rate = F::from_f64(DECELERATION_RATE);
+        (self.deceleration(v) / (rate - F::ONE)).exp()
+    }
+    /// Set the tolerance used to find the time for a position. Only the time is used, since the fling
+    /// stops after its duration.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+    }
+    /// Return the time (in seconds) at which the fling reaches position `p`, or NaN if it never does
+    /// (including positions behind where it started).
+    pub fn time_for_position(&self, p: F) -> F {
+        if p == self.x {
+            return F::ZERO;
+        }
+        let fraction = (p - self.x) / self.distance;
+        if !(fraction > F::ZERO && fraction <= F::ONE) {
+            return F::NAN;
+        }
+        // Find the point on the spline with that much of the distance, and then its time.
+        let s = bisect(F::ZERO, F::ONE, F::EPSILON, |s| {
+            spline_position(s).0 >= fraction
+        });
+        spline_time(s).0 * self.duration
+    }
+    /// Return the velocity (in pixels per second) which travels `distance` before it stops. This is the
+    /// inverse of `distance`.
+    pub fn velocity_for_distance(&self, distance: F) -> F {
+        if distance == F::ZERO {
+            return F::ZERO;
+        }
+        let rate = F::from_f64(DECELERATION_RATE);
+        let coefficient = self.friction * self.physical_coefficient;
+        let deceleration = (rate - F::ONE) / rate * (distance.abs() / coefficient).ln();
+        let v = coefficient * deceleration.exp() / F::from_f64(INFLEXION);
+        if distance < F::ZERO {
+            -v
+        } else {
+            v
+        }
+    }
+    /// Return the scroll friction which makes a fling with velocity `v` travel `distance` before it stops,
+    /// or NaN if there isn't one (because the distance is zero, or in the other direction to the velocity).
+    pub fn friction_for_distance(&self, v: F, distance: F) -> F {
+        if v / distance <= F::ZERO {
+            return F::NAN;
+        }
+        // The distance is (friction * c)^(1 - r / (r - 1)) * (0.35 * |v|)^(r / (r - 1)), where c is the
+        // physical coefficient and r the deceleration rate.
+        let rate = F::from_f64(DECELERATION_RATE);
+        let power = rate / (rate - F::ONE);
+        let speed = F::from_f64(INFLEXION) * v.abs();
+        (speed.powf(power) / distance.abs()).powf(rate - F::ONE) / self.physical_coefficient
+    }
+    /// OverScroller's `getSplineDeceleration`.
+    fn deceleration(&self, v: F) -> F {
+        (F::from_f64(INFLEXION) * v.abs() / (self.friction * self.physical_coefficient)).ln()
+    }
+    /// Return the point on the spline (from 0 to 1) at the given fraction of the duration.
+    fn spline_at(&self, time: F) -> Option<F> {
+        if time == F::ZERO && self.duration > F::ZERO {
+            Some(F::ZERO)
+        } else if time > F::ZERO && time < self.duration {
+            let alpha = time / self.duration;
+            Some(bisect(F::ZERO, F::ONE, F::EPSILON, |s| {
+                spline_time(s).0 >= alpha
+            }))
+        } else {
+            None
+        }
+    }
+}
+impl<F: Float> Simulation<F> for SplineFling<F> {
+    fn x(&self, time: F) -> F {
+        match self.spline_at(time) {
+            Some(s) => self.x + self.distance * spline_position(s).0,
+            None if time <= F::ZERO => self.x,
+            None => self.x + self.distance,
+        }
+    }
+    fn dx(&self, time: F) -> F {
+        match self.spline_at(time) {
+            Some(s) => {
+                let (_, dp, _) = spline_position(s);
+                let (_, dt, _) = spline_time(s);
+                self.distance / self.duration * dp / dt
+            }
+            None => F::ZERO,
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        match self.spline_at(time) {
+            Some(s) => {
+                let (_, dp, ddp) = spline_position(s);
+                let (_, dt, ddt) = spline_time(s);
+                self.distance / (self.duration * self.duration) * (ddp * dt - dp * ddt)
+                    / (dt * dt * dt)
+            }
+            None => F::ZERO,
+        }
+    }
+    fn is_done(&self, time: F) -> bool {
+        time >= self.duration
+    }
+    fn settle_time(&self) -> F {
+        self.duration
+    }
+    fn final_position(&self) -> F {
+        self.x + self.distance
+    }
+}
+impl<F: Float> Resume<F> for SplineFling<F> {
+    fn resume(&mut self, x: F, v: F) {
+        self.set(x, v);
+    }
+}
+impl<F: Float> Fling<F> for SplineFling<F> {
+    fn time_for_position(&self, p: F) -> F {
+        SplineFling::time_for_position(self, p)
+    }
+    fn velocity_for_distance(&self, distance: F) -> F {
+        SplineFling::velocity_for_distance(self, distance)
+    }
+    fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        SplineFling::set_tolerance(self, tolerance);
+    }
+}
+
+/// The fraction of the distance at point `s` along the spline, with its first and second derivatives.
+fn spline_position<F: Float>(s: F) -> (F, F, F) {
+    bezier(F::from_f64(START_TENSION), F::ONE, s)
+}
+/// The fraction of the duration at point `s` along the spline, with its first and second derivatives.
+fn spline_time<F: Float>(s: F) -> (F, F, F) {
+    bezier(
+        F::from_f64(START_TENSION * INFLEXION),
+        F::from_f64(1.0 - END_TENSION * (1.0 - INFLEXION)),
+        s,
+    )
+}
+/// A cubic Bezier curve from 0 to 1 with the given inner control points, with its first and second
+/// derivatives.
+fn bezier<F: Float>(c1: F, c2: F, s: F) -> (F, F, F) {
+    let three = F::from_f64(3.0);
+    let six = F::from_f64(6.0);
+    let r = F::ONE - s;
+    let value = three * r * r * s * c1 + three * r * s * s * c2 + s * s * s;
+    let first = three * r * r * c1 + six * r * s * (c2 - c1) + three * s * s * (F::ONE - c2);
+    let second = six * r * (c2 - c1 - c1) + six * s * (F::ONE - c2 - c2 + c1);
+    (value, first, second)
+}
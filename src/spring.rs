@@ -1,91 +1,239 @@
-use crate::Simulation;
-use core::cmp::Ordering;
+use crate::search::{bisect, decay_time};
+use crate::{Float, Resume, Simulation, Tolerance};
 
 #[derive(PartialEq, Clone, Copy)]
-enum SpringSolution {
-    Overdamped { r1: f32, r2: f32, c1: f32, c2: f32 },
-    CriticallyDamped { r: f32, c1: f32, c2: f32 },
-    Underdamped { w: f32, r: f32, c1: f32, c2: f32 },
+enum SpringSolution<F> {
+    Overdamped { r1: F, r2: F, c1: F, c2: F },
+    CriticallyDamped { r: F, c1: F, c2: F },
+    Underdamped { w: F, r: F, c1: F, c2: F },
     Snapped,
 }
 
-const E: f32 = std::f32::consts::E;
-
-impl SpringSolution {
+impl<F: Float> SpringSolution<F> {
     fn solve(
-        damping: f32,
-        mass: f32,
-        spring_constant: f32,
-        initial: f32,
-        velocity: f32,
-    ) -> SpringSolution {
-        // Solve the quadratic equation
-        let cmk = damping * damping - 4.0 * mass * spring_constant;
-        match cmk.partial_cmp(&0.0) {
-            Some(Ordering::Greater) => {
-                // Overdamped
-                let r1 = (-damping - cmk.sqrt()) / (2.0 * mass);
-                let r2 = (-damping + cmk.sqrt()) / (2.0 * mass);
-                let c2 = (velocity - r1 * initial) / (r2 - r1);
-                let c1 = initial - c2;
-                SpringSolution::Overdamped { r1, r2, c1, c2 }
-            }
-            Some(Ordering::Less) => {
-                // Underdamped
-                let w = (4.0 * mass * spring_constant - damping * damping).sqrt() / (2.0 * mass);
-                let r = -(damping / 2.0 * mass);
-                let c1 = initial;
-                let c2 = (velocity - r * initial) / w;
-                SpringSolution::Underdamped { w, r, c1, c2 }
-            }
-            _ => {
-                // Equal, or close enough.
-                // Critically damped.
-                let r = -damping / (2.0 * mass);
-                let c1 = initial;
-                let c2 = velocity / (r * initial);
-                SpringSolution::CriticallyDamped { r, c1, c2 }
-            }
-        }
-    }
-    fn x(&self, time: f32) -> f32 {
-        match self {
+        damping: F,
+        mass: F,
+        spring_constant: F,
+        initial: F,
+        velocity: F,
+    ) -> SpringSolution<F> {
+        let two = F::from_f64(2.0);
+        // Solve the characteristic equation of m * x'' + c * x' + k * x = 0, which is the quadratic
+        // m * r * r + c * r + k = 0.
+        let cmk = damping * damping - F::from_f64(4.0) * mass * spring_constant;
+        // Close to critical damping the overdamped and underdamped solutions divide by the (tiny)
+        // difference between the roots and lose precision, while the critically damped solution is
+        // accurate to within about the relative size of cmk. So use it when that's close enough.
+        let critical = cmk.abs() <= damping * damping * F::EPSILON.powf(F::from_f64(2.0 / 3.0));
+        if critical || cmk.is_nan() {
+            // Critically damped: x = (c1 + c2 * t) * e^(r * t), with a repeated root.
+            let r = -damping / (two * mass);
+            let c1 = initial;
+            let c2 = velocity - r * initial;
+            SpringSolution::CriticallyDamped { r, c1, c2 }
+        } else if cmk > F::ZERO {
+            // Overdamped: x = c1 * e^(r1 * t) + c2 * e^(r2 * t), with two real roots.
+            let r1 = (-damping - cmk.sqrt()) / (two * mass);
+            let r2 = (-damping + cmk.sqrt()) / (two * mass);
+            let c2 = (velocity - r1 * initial) / (r2 - r1);
+            let c1 = initial - c2;
+            SpringSolution::Overdamped { r1, r2, c1, c2 }
+        } else {
+            // Underdamped: x = e^(r * t) * (c1 * cos(w * t) + c2 * sin(w * t)), with complex roots
+            // r +/- w * i.
+            let w = (-cmk).sqrt() / (two * mass);
+            let r = -damping / (two * mass);
+            let c1 = initial;
+            let c2 = (velocity - r * initial) / w;
+            SpringSolution::Underdamped { w, r, c1, c2 }
+        }
+    }
+    fn x(&self, time: F) -> F {
+        match *self {
             SpringSolution::Overdamped { r1, r2, c1, c2 } => {
-                c1 * E.powf(r1 * time) + c2 * E.powf(r2 * time)
+                c1 * F::E.powf(r1 * time) + c2 * F::E.powf(r2 * time)
+            }
+            SpringSolution::CriticallyDamped { r, c1, c2 } => {
+                (c1 + c2 * time) * F::E.powf(r * time)
             }
-            SpringSolution::CriticallyDamped { r, c1, c2 } => (c1 + c2 * time) * E.powf(r * time),
             SpringSolution::Underdamped { w, r, c1, c2 } => {
-                E.powf(r * time) * (c1 * (w * time).cos() + c2 * (w * time).sin())
+                F::E.powf(r * time) * (c1 * (w * time).cos() + c2 * (w * time).sin())
             }
-            SpringSolution::Snapped => 0.0,
+            SpringSolution::Snapped => F::ZERO,
         }
     }
-    fn dx(&self, time: f32) -> f32 {
-        match self {
+    fn dx(&self, time: F) -> F {
+        match *self {
             SpringSolution::Overdamped { r1, r2, c1, c2 } => {
-                c1 * r1 * E.powf(r1 * time) + c2 * r2 * E.powf(r2 * time)
+                c1 * r1 * F::E.powf(r1 * time) + c2 * r2 * F::E.powf(r2 * time)
             }
             SpringSolution::CriticallyDamped { r, c1, c2 } => {
-                let pow = E.powf(r * time);
+                let pow = F::E.powf(r * time);
                 r * (c1 + c2 * time) * pow + c2 * pow
             }
             SpringSolution::Underdamped { w, r, c1, c2 } => {
-                let pow = E.powf(r * time);
+                let pow = F::E.powf(r * time);
                 let cos = (w * time).cos();
                 let sin = (w * time).sin();
                 pow * (c2 * w * cos - c1 * w * sin) + r * pow * (c2 * sin + c1 * cos)
             }
-            SpringSolution::Snapped => 0.0,
+            SpringSolution::Snapped => F::ZERO,
+        }
+    }
+    fn ddx(&self, time: F) -> F {
+        match *self {
+            SpringSolution::Overdamped { r1, r2, c1, c2 } => {
+                c1 * r1 * r1 * F::E.powf(r1 * time) + c2 * r2 * r2 * F::E.powf(r2 * time)
+            }
+            SpringSolution::CriticallyDamped { r, c1, c2 } => {
+                let pow = F::E.powf(r * time);
+                r * r * (c1 + c2 * time) * pow + (r + r) * c2 * pow
+            }
+            SpringSolution::Underdamped { w, r, c1, c2 } => {
+                let pow = F::E.powf(r * time);
+                let cos = (w * time).cos();
+                let sin = (w * time).sin();
+                // The velocity is pow * (a * cos + b * sin), so differentiate that again.
+                let a = r * c1 + c2 * w;
+                let b = r * c2 - c1 * w;
+                pow * ((r * a + b * w) * cos + (r * b - a * w) * sin)
+            }
+            SpringSolution::Snapped => F::ZERO,
+        }
+    }
+    /// Return a time after which both the position and the velocity stay within the tolerance of zero.
+    /// This uses the envelope of each solution, so it can be a little later than the exact time.
+    fn settle_time(&self, tolerance: Tolerance<F>) -> F {
+        let (x_limit, v_limit) = (tolerance.position, tolerance.velocity);
+        let (position, velocity) = match *self {
+            SpringSolution::Overdamped { r1, r2, c1, c2 } => {
+                // Wait for each of the two terms to be within half of the limit.
+                let half = F::from_f64(0.5);
+                let position_1 = decay_time(c1.abs(), F::ZERO, r1, x_limit * half);
+                let position_2 = decay_time(c2.abs(), F::ZERO, r2, x_limit * half);
+                let velocity_1 = decay_time((c1 * r1).abs(), F::ZERO, r1, v_limit * half);
+                let velocity_2 = decay_time((c2 * r2).abs(), F::ZERO, r2, v_limit * half);
+                (position_1.max(position_2), velocity_1.max(velocity_2))
+            }
+            SpringSolution::CriticallyDamped { r, c1, c2 } => (
+                decay_time(c1.abs(), c2.abs(), r, x_limit),
+                decay_time((r * c1 + c2).abs(), (r * c2).abs(), r, v_limit),
+            ),
+            SpringSolution::Underdamped { w, r, c1, c2 } => {
+                let amplitude = (c1 * c1 + c2 * c2).sqrt();
+                (
+                    decay_time(amplitude, F::ZERO, r, x_limit),
+                    decay_time(amplitude * (w * w + r * r).sqrt(), F::ZERO, r, v_limit),
+                )
+            }
+            SpringSolution::Snapped => (F::ZERO, F::ZERO),
+        };
+        if position.is_nan() || velocity.is_nan() {
+            F::NAN
+        } else if position <= F::ZERO && velocity <= F::ZERO {
+            F::ZERO
+        } else {
+            // The envelope can be exact, so step past the time it reaches the limit.
+            position.max(velocity) + tolerance.time
+        }
+    }
+    /// Return the time of the `index`th local extremum (counting from zero) after time zero, or None if
+    /// there are no more. Between two extrema the solution is monotonic.
+    fn extremum(&self, index: usize) -> Option<F> {
+        let time = match *self {
+            SpringSolution::Overdamped { r1, r2, c1, c2 } if index == 0 => {
+                // Solve c1 * r1 * e^(r1 * t) + c2 * r2 * e^(r2 * t) = 0.
+                (-(c2 * r2) / (c1 * r1)).ln() / (r1 - r2)
+            }
+            SpringSolution::CriticallyDamped { r, c1, c2 } if index == 0 => {
+                // Solve r * c1 + c2 + r * c2 * t = 0.
+                -(r * c1 + c2) / (r * c2)
+            }
+            SpringSolution::Underdamped { w, r, c1, c2 } => {
+                // The velocity is e^(r * t) * (a * cos(w * t) + b * sin(w * t)), which is zero every
+                // half period, starting from the first zero after time zero.
+                let a = r * c1 + c2 * w;
+                let b = r * c2 - c1 * w;
+                let half = F::PI / F::from_f64(2.0);
+                let mut first = b.atan2(a) + half;
+                if first <= F::ZERO {
+                    first += F::PI;
+                } else if first > F::PI {
+                    first -= F::PI;
+                }
+                (first + F::PI * F::from_f64(index as f64)) / w
+            }
+            _ => F::NAN,
+        };
+        if time.is_finite() && time > F::ZERO {
+            Some(time)
+        } else {
+            None
+        }
+    }
+    /// Return the time of the `index`th zero (counting from zero) after time zero, or None if there are no
+    /// more.
+    fn zero(&self, index: usize) -> Option<F> {
+        let time = match *self {
+            SpringSolution::Overdamped { r1, r2, c1, c2 } if index == 0 => {
+                // Solve c1 * e^(r1 * t) = -c2 * e^(r2 * t).
+                (-c2 / c1).ln() / (r1 - r2)
+            }
+            SpringSolution::CriticallyDamped { c1, c2, .. } if index == 0 => -c1 / c2,
+            SpringSolution::Underdamped { w, c1, c2, .. } => {
+                // c1 * cos(w * t) + c2 * sin(w * t) is a sine wave with a phase of atan2(c1, c2), which is
+                // zero every half period.
+                let mut first = -c1.atan2(c2);
+                if first <= F::ZERO {
+                    first += F::PI;
+                }
+                (first + F::PI * F::from_f64(index as f64)) / w
+            }
+            _ => F::NAN,
+        };
+        if time.is_finite() && time > F::ZERO {
+            Some(time)
+        } else {
+            None
+        }
+    }
+    /// Return a bound on the distance from zero at the given time, which decays exponentially.
+    fn envelope(&self, time: F) -> F {
+        match *self {
+            SpringSolution::Overdamped { r1, r2, c1, c2 } => {
+                c1.abs() * (r1 * time).exp() + c2.abs() * (r2 * time).exp()
+            }
+            SpringSolution::CriticallyDamped { r, c1, c2 } => {
+                (c1.abs() + c2.abs() * time) * (r * time).exp()
+            }
+            SpringSolution::Underdamped { r, c1, c2, .. } => {
+                (c1 * c1 + c2 * c2).sqrt() * (r * time).exp()
+            }
+            SpringSolution::Snapped => F::ZERO,
         }
     }
 }
 
-const EPSILON: f32 = 0.001;
-fn almost_equal(a: f32, b: f32, epsilon: f32) -> bool {
+fn almost_equal<F: Float>(a: F, b: F, epsilon: F) -> bool {
     (a > (b - epsilon)) && (a < (b + epsilon))
 }
-fn almost_zero(a: f32, epsilon: f32) -> bool {
-    almost_equal(a, 0.0, epsilon)
+fn almost_zero<F: Float>(a: F, epsilon: F) -> bool {
+    almost_equal(a, F::ZERO, epsilon)
+}
+
+/// a limit on how much a spring can oscillate, set with `Spring::set_limit`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpringLimit {
+    /// The spring oscillates freely. This is the default.
+    Unlimited,
+    /// The spring stops at its end point the first time it gets there, rather than going past it, like
+    /// overshoot clamping in Rebound. A spring which starts at its end point (with only a velocity) stops
+    /// when it comes back to it.
+    ClampOvershoot,
+    /// The spring makes at most this many half oscillations (each ending where it turns around), and
+    /// then moves to its end point as a critically damped spring would, without bouncing again. With zero
+    /// the spring is critically damped from the start. This only affects underdamped springs.
+    MaxBounces(usize),
 }
 
 /// a position controlled by a spring as defined by Hooke's law, `F = -kx * cv`.
@@ -97,74 +245,492 @@ fn almost_zero(a: f32, epsilon: f32) -> bool {
 ///
 /// A critically damped spring satisfies: `damping * damping - 4 * mass * spring_constant == 0`.
 #[derive(Clone, Copy)]
-pub struct Spring {
-    mass: f32,
-    spring_constant: f32,
-    damping: f32,
-    end: f32, // end position
-    solution: SpringSolution,
-    start_time: f32, // typically zero, but not if we were reconfigured while animating.
-}
-impl Spring {
+pub struct Spring<F = f32> {
+    mass: F,
+    spring_constant: F,
+    damping: F,
+    end: F, // end position
+    solution: SpringSolution<F>,
+    start_time: F, // typically zero, but not if we were reconfigured while animating.
+    tolerance: Tolerance<F>,
+    limit: SpringLimit,
+    cutoff: F, // when the limit takes over from the solution, relative to start_time.
+    tail: SpringSolution<F>, // what happens after the cutoff.
+}
+impl Spring<f32> {
     /// Create a new spring with the given mass, spring constant and damping values.
     ///
-    /// The spring starts out "snapped" to 0.0.
-    pub fn new(mass: f32, spring_constant: f32, damping: f32) -> Spring {
+    /// The spring starts out "snapped" to 0.0, and uses the default `Tolerance`. This makes an `f32` spring;
+    /// use `with_constants` for other types.
+    pub fn new(mass: f32, spring_constant: f32, damping: f32) -> Spring<f32> {
+        Spring::with_constants(mass, spring_constant, damping)
+    }
+}
+impl<F: Float> Spring<F> {
+    /// Create a new spring with the given mass, spring constant and damping values, like `new` but for any
+    /// `Float` type.
+    pub fn with_constants(mass: F, spring_constant: F, damping: F) -> Spring<F> {
         Spring {
             mass,
             spring_constant,
             damping,
-            end: 0.0,
+            end: F::ZERO,
             solution: SpringSolution::Snapped, // start out with a snapped spring.
-            start_time: 0.0,
+            start_time: F::ZERO,
+            tolerance: Tolerance::default(),
+            limit: SpringLimit::Unlimited,
+            cutoff: F::INFINITY,
+            tail: SpringSolution::Snapped,
+        }
+    }
+    /// Create a new spring from a perceptual duration (in seconds) and bounce, as used by SwiftUI.
+    ///
+    /// A bounce of 0.0 gives a critically damped spring, a bounce between 0.0 and 1.0 is increasingly
+    /// bouncy, and a negative bounce (down to -1.0) is overdamped. The duration is the period the spring
+    /// would have with no damping, which is roughly how long it takes to get to its end point.
+    pub fn with_duration(duration: F, bounce: F) -> Spring<F> {
+        let damping_ratio = if bounce >= F::ZERO {
+            F::ONE - bounce
+        } else {
+            F::ONE / (F::ONE + bounce)
+        };
+        Spring::with_response(duration, damping_ratio)
+    }
+    /// Create a new spring from a response (in seconds) and damping fraction, as used by UIKit and
+    /// SwiftUI's `interactiveSpring`.
+    ///
+    /// The response is the period the spring would have with no damping. A damping fraction of 1.0 is
+    /// critically damped, less than 1.0 bounces and more than 1.0 is overdamped.
+    pub fn with_response(response: F, damping_fraction: F) -> Spring<F> {
+        let angular_frequency = F::from_f64(2.0) * F::PI / response;
+        Spring::with_stiffness(angular_frequency * angular_frequency, damping_fraction)
+    }
+    /// Create a new spring with a mass of 1.0 from a stiffness and damping ratio, as used by Android's
+    /// `SpringForce`.
+    ///
+    /// A damping ratio of 1.0 is critically damped, less than 1.0 bounces and more than 1.0 is overdamped.
+    pub fn with_stiffness(stiffness: F, damping_ratio: F) -> Spring<F> {
+        let damping = F::from_f64(2.0) * damping_ratio * stiffness.sqrt();
+        Spring::with_constants(F::ONE, stiffness, damping)
+    }
+    /// Return the mass of the spring.
+    pub fn mass(&self) -> F {
+        self.mass
+    }
+    /// Return the spring constant.
+    pub fn spring_constant(&self) -> F {
+        self.spring_constant
+    }
+    /// Return the damping value.
+    pub fn damping(&self) -> F {
+        self.damping
+    }
+    /// Return the stiffness of the spring, as used by `with_stiffness`. This is the spring constant
+    /// of an equivalent spring with a mass of 1.0.
+    pub fn stiffness(&self) -> F {
+        self.spring_constant / self.mass
+    }
+    /// Return the damping ratio (or damping fraction) of the spring, which is 1.0 when the spring is
+    /// critically damped.
+    pub fn damping_ratio(&self) -> F {
+        self.damping / (F::from_f64(2.0) * (self.spring_constant * self.mass).sqrt())
+    }
+    /// Return the response of the spring (in seconds), as used by `with_response`.
+    pub fn response(&self) -> F {
+        F::from_f64(2.0) * F::PI * (self.mass / self.spring_constant).sqrt()
+    }
+    /// Return the perceptual duration of the spring (in seconds), as used by `with_duration`. This is
+    /// the same as the response.
+    pub fn duration(&self) -> F {
+        self.response()
+    }
+    /// Return the bounce of the spring, as used by `with_duration`.
+    pub fn bounce(&self) -> F {
+        let damping_ratio = self.damping_ratio();
+        if damping_ratio <= F::ONE {
+            F::ONE - damping_ratio
+        } else {
+            F::ONE / damping_ratio - F::ONE
         }
     }
+    /// Set the tolerance used to decide when the spring is done (and whether a call to `set` would
+    /// make any difference). The position and velocity are used.
+    pub fn set_tolerance(&mut self, tolerance: Tolerance<F>) {
+        self.tolerance = tolerance;
+        self.apply_limit();
+    }
+    /// Return the tolerance used to decide when the spring is done.
+    pub fn tolerance(&self) -> Tolerance<F> {
+        self.tolerance
+    }
+    /// Set a limit on how much the spring can oscillate. The limit is kept when the spring is set again,
+    /// and also applies to the current motion of the spring.
+    pub fn set_limit(&mut self, limit: SpringLimit) {
+        self.limit = limit;
+        self.apply_limit();
+    }
+    /// Return the limit on how much the spring can oscillate.
+    pub fn limit(&self) -> SpringLimit {
+        self.limit
+    }
     /// Set the spring's endpoint to the given position and velocity. If time is non-zero
     /// then the velocity of the spring at that time (before these new values are applied)
     /// is also included.
-    pub fn set(&mut self, x: f32, velocity: f32, time: f32) {
+    pub fn set(&mut self, x: F, velocity: F, time: F) {
+        let Tolerance {
+            position: x_epsilon,
+            velocity: v_epsilon,
+            ..
+        } = self.tolerance;
         // If this is a request to go where we're already going then ignore it.
-        if almost_equal(x, self.end, EPSILON) && almost_zero(velocity, EPSILON) {
+        if almost_equal(x, self.end, x_epsilon) && almost_zero(velocity, v_epsilon) {
             return;
         }
 
         // If no time was given then don't use the last solution at all.
-        let pos = if time <= 0.0 {
+        let (solution, local) = self.phase(time - self.start_time);
+        let pos = if time <= F::ZERO {
             self.end
         } else {
-            self.end + self.solution.x(time - self.start_time)
+            self.end + solution.x(local)
         };
-        let vel = if time <= 0.0 {
+        let vel = if time <= F::ZERO {
             velocity
         } else {
-            velocity + self.solution.dx(time - self.start_time)
+            velocity + solution.dx(local)
         };
 
         // If we're already at the requested position and there's no velocity then ignore too.
-        if almost_zero(pos - x, EPSILON) && almost_zero(vel, EPSILON) {
+        if almost_zero(pos - x, x_epsilon) && almost_zero(vel, v_epsilon) {
             return;
         }
         self.solution =
             SpringSolution::solve(self.damping, self.mass, self.spring_constant, pos - x, vel);
         self.end = x;
         self.start_time = time;
+        self.apply_limit();
+    }
+    /// Return the first time (in seconds, and not before the spring was last set) at which the spring
+    /// reaches position `p`, or NaN if it never does.
+    pub fn time_for_position(&self, p: F) -> F {
+        self.crossings(p).next().unwrap_or(F::NAN)
+    }
+    /// Return an iterator over every time (in seconds, in order) at which the spring passes through
+    /// position `p`, including the crossings made when the spring overshoots its end point.
+    ///
+    /// An underdamped spring oscillates forever, so crossings stop being reported once the oscillation
+    /// is smaller than the tolerance (or can no longer reach `p`).
+    pub fn crossings(&self, p: F) -> Crossings<F> {
+        Crossings {
+            solution: self.solution,
+            start_time: self.start_time,
+            cutoff: self.cutoff,
+            tail: self.tail,
+            target: p - self.end,
+            tolerance: self.tolerance,
+            segment_start: F::ZERO,
+            index: 0,
+            at_start: true,
+            done: false,
+        }
+    }
+    /// Return an iterator over the times and positions (in order) at which the spring turns around, so the
+    /// first is the furthest it overshoots its end point (unless it was moving away from the end).
+    ///
+    /// These are found analytically. An underdamped spring oscillates forever, so they stop being reported
+    /// once the oscillation is smaller than the position tolerance.
+    pub fn extrema(&self) -> Events<F> {
+        self.events(Event::Extremum)
+    }
+    /// Return an iterator over the times and positions (in order) at which the spring passes through its
+    /// end point, not counting the time the spring was set. Unlike `crossings` these are found analytically.
+    pub fn zero_crossings(&self) -> Events<F> {
+        self.events(Event::Zero)
+    }
+    /// Return the rate (per second, and negative) at which the spring's oscillation (or approach to its end
+    /// point) decays. The distance from the end is bounded by a multiple of `e^(decay_rate * t)`, and each
+    /// bounce of an underdamped spring is `e^(decay_rate * period / 2)` the size of the one before.
+    pub fn decay_rate(&self) -> F {
+        let two = F::from_f64(2.0);
+        let cmk = self.damping * self.damping - F::from_f64(4.0) * self.mass * self.spring_constant;
+        if cmk > F::ZERO {
+            // The slower of the two exponentials.
+            (-self.damping + cmk.sqrt()) / (two * self.mass)
+        } else {
+            -self.damping / (two * self.mass)
+        }
+    }
+    /// Return a bound on how far the spring is from its end point at the given time. For an underdamped
+    /// spring this is the envelope of its oscillation.
+    pub fn envelope(&self, time: F) -> F {
+        let (solution, local) = self.phase(time - self.start_time);
+        solution.envelope(local)
+    }
+    /// Return the period of the spring's oscillation (in seconds), which is twice the time between bounces,
+    /// or infinity if the spring doesn't oscillate.
+    pub fn period(&self) -> F {
+        let cmk = self.damping * self.damping - F::from_f64(4.0) * self.mass * self.spring_constant;
+        if cmk < F::ZERO {
+            F::from_f64(4.0) * F::PI * self.mass / (-cmk).sqrt()
+        } else {
+            F::INFINITY
+        }
+    }
+    fn events(&self, event: Event) -> Events<F> {
+        Events {
+            solution: self.solution,
+            start_time: self.start_time,
+            cutoff: self.cutoff,
+            tail: self.tail,
+            end: self.end,
+            tolerance: self.tolerance,
+            event,
+            index: 0,
+        }
     }
     /// "Snap" the spring and set the value. The spring simulation will return this value
     /// with no velocity for all time (or until set is called again) once snapped.
-    pub fn snap(&mut self, x: f32) {
+    pub fn snap(&mut self, x: F) {
         self.end = x;
-        self.start_time = 0.0;
+        self.start_time = F::ZERO;
         self.solution = SpringSolution::Snapped;
+        self.apply_limit();
+    }
+    /// Work out when the limit takes over from the solution, and what happens after that.
+    fn apply_limit(&mut self) {
+        self.cutoff = F::INFINITY;
+        self.tail = SpringSolution::Snapped;
+        match self.limit {
+            SpringLimit::Unlimited => {}
+            SpringLimit::ClampOvershoot => {
+                // Stop at the end from the first time it's reached, unless that's where we started.
+                let mut crossings = self.crossings(self.end);
+                while let Some(time) = crossings.next_local() {
+                    if time > F::ZERO {
+                        self.cutoff = time;
+                        break;
+                    }
+                }
+            }
+            SpringLimit::MaxBounces(bounces) => {
+                if let SpringSolution::Underdamped { .. } = self.solution {
+                    let start = if bounces == 0 {
+                        Some(F::ZERO)
+                    } else {
+                        self.solution.extremum(bounces - 1)
+                    };
+                    if let Some(start) = start {
+                        // Continue from where the last bounce turned around with a critically damped
+                        // spring, which can't bounce again. The velocity there is zero, but computing it
+                        // leaves a tiny remainder which would give the tail an extremum of its own just
+                        // after the cutoff.
+                        let velocity = if bounces == 0 {
+                            self.solution.dx(start)
+                        } else {
+                            F::ZERO
+                        };
+                        let critical = F::from_f64(2.0) * (self.mass * self.spring_constant).sqrt();
+                        self.tail = SpringSolution::solve(
+                            critical,
+                            self.mass,
+                            self.spring_constant,
+                            self.solution.x(start),
+                            velocity,
+                        );
+                        self.cutoff = start;
+                    }
+                }
+            }
+        }
+    }
+    /// Return the solution in use at the given time (relative to start_time), and the time to evaluate
+    /// it at.
+    fn phase(&self, time: F) -> (SpringSolution<F>, F) {
+        if time >= self.cutoff {
+            (self.tail, time - self.cutoff)
+        } else {
+            (self.solution, time)
+        }
     }
 }
-impl Simulation for Spring {
-    fn x(&self, time: f32) -> f32 {
-        self.end + self.solution.x(time - self.start_time)
+impl<F: Float> Simulation<F> for Spring<F> {
+    fn x(&self, time: F) -> F {
+        let (solution, local) = self.phase(time - self.start_time);
+        self.end + solution.x(local)
     }
-    fn dx(&self, time: f32) -> f32 {
-        self.solution.dx(time - self.start_time)
+    fn dx(&self, time: F) -> F {
+        let (solution, local) = self.phase(time - self.start_time);
+        solution.dx(local)
     }
-    fn is_done(&self, time: f32) -> bool {
-        almost_equal(self.x(time), self.end, EPSILON) && almost_zero(self.dx(time), EPSILON)
+    fn ddx(&self, time: F) -> F {
+        let (solution, local) = self.phase(time - self.start_time);
+        solution.ddx(local)
+    }
+    fn is_done(&self, time: F) -> bool {
+        almost_equal(self.x(time), self.end, self.tolerance.position)
+            && almost_zero(self.dx(time), self.tolerance.velocity)
+    }
+    /// Return the time after which the spring is certain to be done. This is found from the decay of
+    /// the spring's oscillation (or approach), so it can be a little later than the first time that
+    /// `is_done` returns true.
+    fn settle_time(&self) -> F {
+        let settle = self.solution.settle_time(self.tolerance);
+        if !self.cutoff.is_finite() || settle <= self.cutoff {
+            self.start_time + settle
+        } else {
+            self.start_time + self.cutoff + self.tail.settle_time(self.tolerance)
+        }
+    }
+    fn final_position(&self) -> F {
+        self.end
+    }
+}
+impl<F: Float> Resume<F> for Spring<F> {
+    /// Start the spring from position `x` with velocity `v`, keeping the current end point.
+    fn resume(&mut self, x: F, v: F) {
+        let end = self.end;
+        self.snap(x);
+        self.set(end, v, F::ZERO);
+    }
+}
+
+/// an iterator over the times at which a spring passes through a position, created by `Spring::crossings`.
+#[derive(Clone, Copy)]
+pub struct Crossings<F = f32> {
+    solution: SpringSolution<F>,
+    start_time: F,
+    cutoff: F, // when to move on to the tail, relative to start_time.
+    tail: SpringSolution<F>,
+    target: F, // the position relative to the spring's end.
+    tolerance: Tolerance<F>,
+    segment_start: F, // relative to start_time.
+    index: usize,     // of the extremum that ends the current segment.
+    at_start: bool,
+    done: bool,
+}
+impl<F: Float> Crossings<F> {
+    /// Return the next crossing of the current solution, relative to its start time.
+    fn next_local(&mut self) -> Option<F> {
+        // The solution is monotonic between each pair of extrema, so there can be at most one crossing
+        // in each of those segments, which we can find by bisection.
+        let target = self.target;
+        if self.at_start {
+            self.at_start = false;
+            if self.solution.x(F::ZERO) == target {
+                return Some(F::ZERO);
+            }
+        }
+        while !self.done {
+            let start = self.segment_start;
+            let end = match self.solution.extremum(self.index) {
+                Some(end) => end,
+                None => {
+                    // After the last extremum the solution heads straight for zero, so find a time
+                    // by which it is closer to zero than the target is.
+                    self.done = true;
+                    let closer = Tolerance {
+                        position: target.abs(),
+                        velocity: F::INFINITY,
+                        ..self.tolerance
+                    };
+                    let end = self.solution.settle_time(closer);
+                    if target == F::ZERO || !end.is_finite() || end <= start {
+                        return None;
+                    }
+                    end
+                }
+            };
+            if let SpringSolution::Underdamped { r, c1, c2, .. } = self.solution {
+                // Stop once the oscillation can't reach the target (or is too small to matter).
+                let envelope = (c1 * c1 + c2 * c2).sqrt() * (r * start).exp();
+                if envelope < target.abs().max(self.tolerance.position) {
+                    self.done = true;
+                    return None;
+                }
+            }
+            self.index += 1;
+            self.segment_start = end;
+            let before = self.solution.x(start) - target;
+            let after = self.solution.x(end) - target;
+            // A crossing exactly at the start of the segment was reported with the previous segment.
+            if after == F::ZERO || (before != F::ZERO && (before < F::ZERO) != (after < F::ZERO)) {
+                let rising = after > before;
+                let time = bisect(start, end, self.tolerance.time, |t| {
+                    (self.solution.x(t) >= target) == rising
+                });
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+impl<F: Float> Iterator for Crossings<F> {
+    type Item = F;
+    fn next(&mut self) -> Option<F> {
+        loop {
+            match self.next_local() {
+                Some(time) if time <= self.cutoff => return Some(self.start_time + time),
+                _ if self.cutoff.is_finite() => {
+                    // Carry on with the tail, which starts at the cutoff. A crossing right at the start
+                    // of the tail was already reported.
+                    self.solution = self.tail;
+                    self.start_time += self.cutoff;
+                    self.cutoff = F::INFINITY;
+                    self.segment_start = F::ZERO;
+                    self.index = 0;
+                    self.at_start = false;
+                    self.done = false;
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Event {
+    Extremum,
+    Zero,
+}
+
+/// an iterator over the times and positions of a spring's extrema or zero crossings, created by
+/// `Spring::extrema` and `Spring::zero_crossings`.
+#[derive(Clone, Copy)]
+pub struct Events<F = f32> {
+    solution: SpringSolution<F>,
+    start_time: F,
+    cutoff: F, // when to move on to the tail, relative to start_time.
+    tail: SpringSolution<F>,
+    end: F,
+    tolerance: Tolerance<F>,
+    event: Event,
+    index: usize,
+}
+impl<F: Float> Iterator for Events<F> {
+    type Item = (F, F);
+    fn next(&mut self) -> Option<(F, F)> {
+        loop {
+            let time = match self.event {
+                Event::Extremum => self.solution.extremum(self.index),
+                Event::Zero => self.solution.zero(self.index),
+            };
+            match time {
+                Some(time) if time <= self.cutoff + self.tolerance.time => {
+                    // Stop once an underdamped spring's oscillation is too small to matter.
+                    if self.solution.envelope(time) < self.tolerance.position {
+                        return None;
+                    }
+                    self.index += 1;
+                    return Some((self.start_time + time, self.end + self.solution.x(time)));
+                }
+                _ if self.cutoff.is_finite() => {
+                    self.solution = self.tail;
+                    self.start_time += self.cutoff;
+                    self.cutoff = F::INFINITY;
+                    self.index = 0;
+                }
+                _ => return None,
+            }
+        }
     }
 }
@@ -0,0 +1,239 @@
+use crate::search::bisect;
+use crate::{Float, Spring, SpringLimit, Tolerance};
+
+/// the step response of a spring: how it moves to its end point when released from rest.
+///
+/// These are the usual measures from control theory. They only depend on the mass, spring constant and
+/// damping of the spring (not on where it is or how fast it's going), and the overshoot and settling
+/// tolerance are fractions of the distance the spring starts from its end point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringMetrics<F = f32> {
+    /// How far the spring goes past its end point, as a fraction of the starting distance. This is zero
+    /// for a spring that doesn't bounce.
+    pub overshoot: F,
+    /// The time (in seconds) at which the spring is furthest past its end point, or infinity for a
+    /// spring that doesn't bounce.
+    pub peak_time: F,
+    /// The time (in seconds) taken to go from 10% to 90% of the way to the end point.
+    pub rise_time: F,
+    /// The time (in seconds) after which the spring stays within the settling tolerance of its end point.
+    pub settling_time: F,
+}
+
+/// a specification for a spring, in terms of its step response, which can be solved for the spring's
+/// mass, spring constant and damping.
+///
+/// A spring has two parameters which shape its motion (the damping ratio and the natural frequency), so
+/// two targets are needed: the overshoot and one time, or two times. If only one time is given then the
+/// spring is critically damped, so that it doesn't overshoot.
+///
+/// The settling time doesn't change smoothly with the damping: it jumps each time another bounce starts or
+/// stops reaching the settling tolerance. So two times which include the settling time may be met by several
+/// springs, in which case the most damped is used, or by none, in which case `build` returns None.
+///
+/// ```
+/// use gravitas::{Spring, SpringSpec};
+///
+/// // At most 5% overshoot, and settled to within 2% by 400ms.
+/// let spring: Spring = SpringSpec::new()
+///     .overshoot(0.05)
+///     .settling_time(0.4, 0.02)
+///     .build()
+///     .unwrap();
+/// let metrics = spring.metrics(0.02);
+/// assert!((metrics.overshoot - 0.05).abs() < 0.001);
+/// assert!((metrics.settling_time - 0.4).abs() < 0.001);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SpringSpec<F = f32> {
+    mass: F,
+    overshoot: Option<F>,
+    peak_time: Option<F>,
+    rise_time: Option<F>,
+    settling_time: Option<(F, F)>,
+}
+impl<F: Float> SpringSpec<F> {
+    /// Create a new, empty, specification for a spring with a mass of 1.0.
+    pub fn new() -> SpringSpec<F> {
+        SpringSpec {
+            mass: F::ONE,
+            overshoot: None,
+            peak_time: None,
+            rise_time: None,
+            settling_time: None,
+        }
+    }
+    /// Use the given mass for the spring. This doesn't change how the spring moves, only the spring
+    /// constant and damping values that are needed.
+    pub fn mass(mut self, mass: F) -> SpringSpec<F> {
+        self.mass = mass;
+        self
+    }
+    /// Set how far the spring should go past its end point, as a fraction of its starting distance
+    /// (so 0.05 is 5% overshoot).
+    pub fn overshoot(mut self, overshoot: F) -> SpringSpec<F> {
+        self.overshoot = Some(overshoot);
+        self
+    }
+    /// Set the time (in seconds) at which the spring should be furthest past its end point.
+    pub fn peak_time(mut self, time: F) -> SpringSpec<F> {
+        self.peak_time = Some(time);
+        self
+    }
+    /// Set the time (in seconds) the spring should take to go from 10% to 90% of the way to its end point.
+    pub fn rise_time(mut self, time: F) -> SpringSpec<F> {
+        self.rise_time = Some(time);
+        self
+    }
+    /// Set the time (in seconds) after which the spring should stay within `tolerance` of its end point,
+    /// where `tolerance` is a fraction of its starting distance (so 0.02 is within 2%).
+    pub fn settling_time(mut self, time: F, tolerance: F) -> SpringSpec<F> {
+        self.settling_time = Some((time, tolerance));
+        self
+    }
+    /// Solve for a spring which meets this specification, or return None if the specification has too
+    /// many or too few targets, or can't be met.
+    pub fn build(&self) -> Option<Spring<F>> {
+        let times = [
+            self.peak_time.map(|time| (Target::Peak, time)),
+            self.rise_time.map(|time| (Target::Rise, time)),
+            self.settling_time
+                .map(|(time, tolerance)| (Target::Settling(tolerance), time)),
+        ];
+        let mut given = times.iter().flatten();
+        let (first, first_time) = *given.next()?;
+        let second = given.next().copied();
+        if given.next().is_some() {
+            return None;
+        }
+        let damping_ratio = match (self.overshoot, second) {
+            (Some(_), Some(_)) => return None,
+            (Some(overshoot), None) => {
+                if overshoot <= F::ZERO {
+                    F::ONE
+                } else if overshoot < F::ONE {
+                    // Invert overshoot = e^(-zeta * pi / sqrt(1 - zeta * zeta)).
+                    let ln = overshoot.ln();
+                    -ln / (F::PI * F::PI + ln * ln).sqrt()
+                } else {
+                    return None;
+                }
+            }
+            (None, None) => F::ONE,
+            (None, Some((second, second_time))) => {
+                solve_damping_ratio(first, second, first_time / second_time)?
+            }
+        };
+        // Every time scales with 1 / natural_frequency, so compare the target with the time for a
+        // natural frequency of one.
+        let natural_frequency = first.time(damping_ratio) / first_time;
+        if !natural_frequency.is_finite() || natural_frequency <= F::ZERO {
+            return None;
+        }
+        let spring_constant = self.mass * natural_frequency * natural_frequency;
+        let damping = F::from_f64(2.0) * damping_ratio * self.mass * natural_frequency;
+        Some(Spring::with_constants(self.mass, spring_constant, damping))
+    }
+}
+impl<F: Float> Default for SpringSpec<F> {
+    fn default() -> SpringSpec<F> {
+        SpringSpec::new()
+    }
+}
+
+impl<F: Float> Spring<F> {
+    /// Return the step response metrics of this spring, with the settling time measured to within
+    /// `settling_tolerance` (a fraction of the starting distance, so 0.02 is within 2%). The spring's limit
+    /// is taken into account, so a spring which clamps its overshoot has none.
+    pub fn metrics(&self, settling_tolerance: F) -> SpringMetrics<F> {
+        let mut step = Spring::with_constants(self.mass(), self.spring_constant(), self.damping());
+        step.set_tolerance(Tolerance {
+            position: settling_tolerance * F::from_f64(0.5),
+            ..Tolerance::default()
+        });
+        step.set_limit(self.limit());
+        // Release the spring from rest, one unit away from its end.
+        step.snap(F::ONE);
+        step.set(F::ZERO, F::ZERO, F::ZERO);
+
+        let damping_ratio = self.damping_ratio();
+        // Both limits stop the spring before its first peak, except for a spring allowed to bounce.
+        let bounces = match self.limit() {
+            SpringLimit::Unlimited => true,
+            SpringLimit::ClampOvershoot => false,
+            SpringLimit::MaxBounces(bounces) => bounces > 0,
+        };
+        let (overshoot, peak_time) = if damping_ratio < F::ONE && bounces {
+            let root = (F::ONE - damping_ratio * damping_ratio).sqrt();
+            let natural_frequency = (self.spring_constant() / self.mass()).sqrt();
+            (
+                (-damping_ratio * F::PI / root).exp(),
+                F::PI / (natural_frequency * root),
+            )
+        } else {
+            (F::ZERO, F::INFINITY)
+        };
+        let tenth = F::from_f64(0.1);
+        let rise_time = step.time_for_position(tenth) - step.time_for_position(F::ONE - tenth);
+        // The last time the spring leaves the tolerance on either side of the end.
+        let settling_time = step
+            .crossings(settling_tolerance)
+            .chain(step.crossings(-settling_tolerance))
+            .fold(F::ZERO, |last, time| last.max(time));
+        SpringMetrics {
+            overshoot,
+            peak_time,
+            rise_time,
+            settling_time,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Target<F> {
+    Peak,
+    Rise,
+    Settling(F),
+}
+impl<F: Float> Target<F> {
+    /// Return this time for a spring with the given damping ratio and a natural frequency of one.
+    fn time(&self, damping_ratio: F) -> F {
+        let spring = Spring::with_constants(F::ONE, F::ONE, F::from_f64(2.0) * damping_ratio);
+        match *self {
+            Target::Peak => spring.metrics(F::ONE).peak_time,
+            Target::Rise => spring.metrics(F::ONE).rise_time,
+            Target::Settling(tolerance) => spring.metrics(tolerance).settling_time,
+        }
+    }
+}
+
+/// Find the damping ratio which gives the requested ratio between two times, or None if there isn't one.
+///
+/// The settling time jumps whenever another bounce starts or stops reaching the settling tolerance, so the
+/// ratio isn't monotonic (or even continuous) in the damping ratio. So sample it to find each place where it
+/// passes the requested ratio, bisect each of those, and keep the most damped one which really meets the
+/// ratio rather than straddling a jump.
+fn solve_damping_ratio<F: Float>(first: Target<F>, second: Target<F>, ratio: F) -> Option<F> {
+    let ratio_for = |damping_ratio: F| first.time(damping_ratio) / second.time(damping_ratio);
+    let peak = matches!(first, Target::Peak) || matches!(second, Target::Peak);
+    // Only bouncing springs have a peak.
+    let low = F::from_f64(0.01);
+    let high = F::from_f64(if peak { 0.99 } else { 4.0 });
+    let samples = 64;
+    let at = |i: usize| low + (high - low) * F::from_f64(i as f64 / samples as f64);
+    let above = |damping_ratio: F| ratio_for(damping_ratio) > ratio;
+    let mut upper_above = above(high);
+    for i in (0..samples).rev() {
+        let (lower, upper) = (at(i), at(i + 1));
+        let lower_above = above(lower);
+        if lower_above != upper_above {
+            let damping_ratio = bisect(lower, upper, F::ZERO, |d| above(d) != lower_above);
+            let error = (ratio_for(damping_ratio) - ratio) / ratio;
+            if error.abs() < F::from_f64(0.001) {
+                return Some(damping_ratio);
+            }
+        }
+        upper_above = lower_above;
+    }
+    None
+}
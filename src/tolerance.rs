@@ -0,0 +1,32 @@
+use crate::Float;
+
+/// how close a simulation needs to get to its final state before it is done.
+///
+/// The defaults suit positions in pixels. Animating values in other units, such as opacity (from 0 to 1)
+/// or rotation (in radians), needs tolerances to match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance<F = f32> {
+    /// How close (in units of position) the position needs to be to its final value.
+    pub position: F,
+    /// How close (in units of position per second) the velocity needs to be to zero.
+    pub velocity: F,
+    /// How precisely (in seconds) to find times that have to be searched for rather than solved directly.
+    pub time: F,
+}
+impl<F: Float> Tolerance<F> {
+    /// Create a new tolerance with the given position, velocity and time values.
+    pub fn new(position: F, velocity: F, time: F) -> Tolerance<F> {
+        Tolerance {
+            position,
+            velocity,
+            time,
+        }
+    }
+}
+impl<F: Float> Default for Tolerance<F> {
+    /// A tolerance of 0.001 for position and velocity, and a microsecond for time.
+    fn default() -> Tolerance<F> {
+        let epsilon = F::from_f64(0.001);
+        Tolerance::new(epsilon, epsilon, F::from_f64(0.000001))
+    }
+}
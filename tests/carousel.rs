@@ -0,0 +1,54 @@
+use gravitas::{Carousel, Handoff, Resume, Simulation, Spring, Trigger};
+
+#[test]
+fn test_carousel() {
+    let mut c: Carousel<f64> = Carousel::new(0.05, 30.0);
+    // A fling slows down to the cruising speed.
+    c.set(10.0, 600.0);
+    assert_eq!(c.x(0.0), 10.0);
+    assert_eq!(c.dx(0.0), 600.0);
+    let t = c.cruise_time();
+    assert!((c.dx(t) - 31.0).abs() < 1e-6);
+    assert!((c.dx(20.0) - 30.0).abs() < 1e-6);
+    // It never stops.
+    assert!(!c.is_done(1000.0));
+    assert!(c.settle_time().is_nan() && c.final_position().is_nan());
+    for i in 1..40 {
+        let t = i as f64 * 0.25;
+        let h = 1e-5;
+        assert!((c.dx(t) - (c.x(t + h) - c.x(t - h)) / (2.0 * h)).abs() < 1e-3);
+        assert!((c.ddx(t) - (c.dx(t + h) - c.dx(t - h)) / (2.0 * h)).abs() < 1e-3);
+    }
+    let t = c.time_for_position(1000.0);
+    assert!((c.x(t) - 1000.0).abs() < 0.01);
+    assert!(c.time_for_position(0.0).is_nan());
+
+    // A slow fling the other way speeds up to cruise backwards.
+    c.set(10.0, -5.0);
+    assert_eq!(c.cruise_velocity(), -30.0);
+    assert!(c.dx(1.0) < -5.0);
+    assert!((c.dx(20.0) + 30.0).abs() < 1e-6);
+    let t = c.time_for_position(-100.0);
+    assert!((c.x(t) + 100.0).abs() < 0.01);
+
+    // Without a cruising speed it's the same as friction.
+    c.set_cruise(0.0);
+    c.set(10.0, -300.0);
+    assert!((c.final_position() - (10.0 + 300.0 / 0.05f64.ln())).abs() < 1e-9);
+    assert!(c.is_done(c.settle_time() + 0.01));
+    assert!(c.time_for_position(c.final_position() - 1.0).is_nan());
+}
+
+#[test]
+fn test_carousel_resume() {
+    // After a gesture (here a spring) hands over, the carousel goes back to cruising.
+    let carousel: Carousel<f64> = Carousel::new(0.05, 30.0);
+    let mut spring = Spring::with_constants(1.0, 200.0, 20.0);
+    spring.set(100.0, 0.0, 0.0);
+    let h = Handoff::new(spring, carousel, Trigger::Time(0.1));
+    let (x, v) = (h.x(0.1), h.dx(0.1));
+    let mut c = carousel;
+    c.resume(x, v);
+    assert!((h.x(0.5) - c.x(0.4)).abs() < 1e-9);
+    assert!((h.dx(10.0) - 30.0).abs() < 1e-6);
+}
@@ -0,0 +1,58 @@
+use gravitas::{Gravity, Simulation, Spring};
+
+#[test]
+fn test_offset_scale() {
+    let mut g: Gravity = Gravity::new(10.0);
+    g.set(0.0, 5.0);
+    let s = g.offset(100.0).scale(0.5);
+    assert_eq!(s.x(0.0), 50.0);
+    assert_eq!(s.x(1.0), 55.0);
+    assert_eq!(s.dx(1.0), 7.5);
+    assert_eq!(s.inner().inner().x(1.0), 10.0);
+}
+
+#[test]
+fn test_delay_time_scale() {
+    let mut g: Gravity = Gravity::new(10.0);
+    g.set(0.0, 5.0);
+    let d = g.delay(1.0);
+    assert_eq!(d.x(1.0), 0.0);
+    assert_eq!(d.x(2.0), g.x(1.0));
+    assert_eq!(d.dx(2.0), g.dx(1.0));
+    let t = g.time_scale(2.0);
+    assert_eq!(t.x(1.0), g.x(2.0));
+    assert_eq!(t.dx(1.0), 2.0 * g.dx(2.0));
+}
+
+#[test]
+fn test_delay_before_start() {
+    // Before the delay the spring holds still where it starts.
+    let mut s: Spring = Spring::new(1.0, 90.0, 20.0);
+    s.snap(100.0);
+    s.set(0.0, 0.0, 0.0);
+    let d = s.delay(1.0);
+    for &t in &[0.0, 0.5, 0.999] {
+        assert_eq!(d.x(t), 100.0);
+        assert_eq!(d.dx(t), 0.0);
+        assert_eq!(d.ddx(t), 0.0);
+        assert!(!d.is_done(t));
+    }
+    assert_eq!(d.x(1.0), s.x(0.0));
+    assert_eq!(d.x(1.5), s.x(0.5));
+    assert_eq!(d.dx(1.5), s.dx(0.5));
+    assert!(d.is_done(d.settle_time()));
+}
+
+#[test]
+fn test_clamp_map() {
+    let mut g: Gravity = Gravity::new(10.0);
+    g.set(0.0, 5.0);
+    let c = g.clamp(0.0, 10.0);
+    assert_eq!(c.x(0.5), g.x(0.5));
+    assert_eq!(c.dx(0.5), g.dx(0.5));
+    assert_eq!(c.x(2.0), 10.0);
+    assert_eq!(c.dx(2.0), 0.0);
+    let m = g.map(|x| x * x, |x| 2.0 * x);
+    assert_eq!(m.x(1.0), 100.0);
+    assert_eq!(m.dx(1.0), 2.0 * g.x(1.0) * g.dx(1.0));
+}
@@ -0,0 +1,53 @@
+use gravitas::{Deceleration, Friction, Simulation};
+
+#[test]
+fn test_deceleration() {
+    let mut d: Deceleration<f64> = Deceleration::normal();
+    assert_eq!(d.rate(), 0.998);
+    d.set(100.0, 2000.0);
+    // UIKit's projection: (2000 / 1000) * 0.998 / 0.002.
+    assert!((d.project(2000.0) - 998.0).abs() < 1e-9);
+    assert!((d.final_position() - 1098.0).abs() < 1e-9);
+    assert_eq!(d.x(0.0), 100.0);
+    assert!((d.dx(0.0) - 2000.0).abs() < 4.0);
+    // The velocity drops by the rate every millisecond.
+    for i in 0..10 {
+        let t = i as f64 * 0.1;
+        assert!((d.dx(t + 0.001) / d.dx(t) - 0.998).abs() < 1e-9);
+        let h = 0.000001;
+        assert!((d.ddx(t) - (d.dx(t + h) - d.dx(t - h)) / (2.0 * h)).abs() < 0.01);
+    }
+    let t = d.time_for_position(1000.0);
+    assert!((d.x(t) - 1000.0).abs() < 1e-6);
+    assert!(d.time_for_position(1100.0).is_nan());
+    assert!(d.time_for_position(0.0) < 0.0);
+    let settle = d.settle_time();
+    assert!(d.is_done(settle + 1e-6) && !d.is_done(settle - 0.01));
+
+    // It moves like friction with a drag of rate^1000, scaled slightly to land on the projection.
+    let fast: Deceleration<f64> = Deceleration::fast();
+    let mut f: Friction<f64> = Friction::with_drag(0.99f64.powi(1000));
+    f.set(0.0, 1000.0);
+    let mut d = fast;
+    d.set(0.0, 1000.0);
+    let scale = d.final_position() / f.final_position();
+    assert!((scale - 1.0).abs() < 0.01);
+    for i in 0..10 {
+        let t = i as f64 * 0.05;
+        assert!((d.x(t) - f.x(t) * scale).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_deceleration_inverse() {
+    let mut d: Deceleration<f64> = Deceleration::fast();
+    let v = d.velocity_for_distance(120.0);
+    assert!((d.project(v) - 120.0).abs() < 1e-9);
+    d.set(10.0, v);
+    assert!((d.final_position() - 130.0).abs() < 1e-9);
+
+    let rate: f64 = Deceleration::rate_for_distance(-2000.0, -700.0);
+    let d = Deceleration::new(rate);
+    assert!((d.project(-2000.0) + 700.0).abs() < 1e-9);
+    assert!(Deceleration::<f64>::rate_for_distance(2000.0, 0.0).is_nan());
+}
@@ -0,0 +1,62 @@
+use gravitas::{Friction, Simulation, Spring};
+
+fn sample(s: &impl Simulation<f64>, start: f64, noise: f64) -> Vec<(f64, f64)> {
+    // A 60Hz capture, with some repeatable noise.
+    (0..60)
+        .map(|i| {
+            let t = i as f64 / 60.0;
+            let wobble = noise * ((i * 7919 % 13) as f64 / 6.0 - 1.0);
+            (start + t, s.x(t) + wobble)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fit_spring() {
+    // Underdamped, critically damped and overdamped springs, with masses other than one.
+    for &(m, k, c) in &[(1.0, 300.0, 8.0), (2.0, 200.0, 40.0), (0.5, 100.0, 30.0)] {
+        let mut s: Spring<f64> = Spring::with_constants(m, k, c);
+        s.snap(100.0);
+        s.set(0.0, -400.0, 0.0);
+        let fit = Spring::fit(&sample(&s, 0.0, 0.0)).unwrap();
+        assert!(fit.residual < 1e-4);
+        assert_eq!(fit.spring.mass(), 1.0);
+        assert!((fit.spring.stiffness() - k / m).abs() < 0.01 * k / m);
+        assert!((fit.spring.damping_ratio() - s.damping_ratio()).abs() < 0.01);
+        assert!((fit.position - 100.0).abs() < 0.01);
+        assert!((fit.velocity + 400.0).abs() < 1.0);
+        assert!(fit.spring.final_position().abs() < 0.01);
+    }
+    // Noisy samples which don't start at zero.
+    let mut s: Spring<f64> = Spring::with_response(0.4, 0.6);
+    s.snap(0.0);
+    s.set(1.0, 0.0, 0.0);
+    let samples = sample(&s, 3.0, 0.01);
+    let fit = Spring::fit(&samples).unwrap();
+    assert!(fit.residual < 0.02);
+    assert!((fit.spring.response() - 0.4).abs() < 0.01);
+    assert!((fit.spring.damping_ratio() - 0.6).abs() < 0.02);
+    for i in 0..20 {
+        let t = i as f64 * 0.05;
+        assert!((fit.spring.x(t) - s.x(t)).abs() < 0.02);
+    }
+    assert!(Spring::<f64>::fit(&samples[..3]).is_none());
+}
+
+#[test]
+fn test_fit_friction() {
+    let mut f: Friction<f64> = Friction::with_drag(0.02);
+    f.set(10.0, 2000.0);
+    let fit = Friction::fit(&sample(&f, 1.0, 0.0)).unwrap();
+    assert!(fit.residual < 1e-6);
+    assert!((fit.friction.drag() - 0.02).abs() < 1e-5);
+    assert!((fit.position - 10.0).abs() < 1e-4);
+    assert!((fit.velocity - 2000.0).abs() < 0.01);
+
+    // With noise the fit is close, and reports the noise as the residual.
+    let samples = sample(&f, 0.0, 0.5);
+    let fit = Friction::fit(&samples).unwrap();
+    assert!(fit.residual > 0.1 && fit.residual < 1.0);
+    assert!((fit.friction.final_position() - f.final_position()).abs() < 2.0);
+    assert!(Friction::<f64>::fit(&samples[..2]).is_none());
+}
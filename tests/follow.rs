@@ -0,0 +1,79 @@
+use gravitas::{Follower, Friction, MovingSpring, Simulation, Spring};
+
+#[test]
+fn test_moving_spring() {
+    // Chase a target moving at 100 units per second from 50 units away.
+    let (m, k, c): (f64, f64, f64) = (1.0, 90.0, 20.0);
+    let mut s: MovingSpring<f64> = MovingSpring::new(m, k, c);
+    s.snap(50.0);
+    s.set(0.0, 100.0, 0.0);
+    assert!((s.x(0.0) - 50.0).abs() < 1e-9);
+    assert!(s.dx(0.0).abs() < 1e-9);
+    // The equation of motion holds against the moving end.
+    for i in 0..20 {
+        let t = i as f64 * 0.05;
+        let a = -(c * s.dx(t) + k * (s.x(t) - s.end(t))) / m;
+        assert!((s.ddx(t) - a).abs() < 1e-6);
+    }
+    // Once caught up it follows at a constant lag, and is never done.
+    let t = s.catch_up_time();
+    assert!(t.is_finite());
+    assert!((s.end(t + 1.0) - s.x(t + 1.0) - s.lag()).abs() < 0.01);
+    assert!((s.dx(t + 1.0) - 100.0).abs() < 0.01);
+    assert!(!s.is_done(t + 1.0));
+    assert!(s.settle_time().is_nan());
+
+    // Stopping the end gives an ordinary spring.
+    s.set(s.end(2.0), 0.0, 2.0);
+    let mut spring: Spring<f64> = Spring::with_constants(m, k, c);
+    spring.snap(s.x(2.0));
+    spring.set(s.end(2.0), s.dx(2.0), 0.0);
+    for i in 0..20 {
+        let t = i as f64 * 0.05;
+        assert!((s.x(2.0 + t) - spring.x(t)).abs() < 1e-9);
+    }
+    assert_eq!(s.final_position(), 200.0);
+    assert!(s.is_done(s.settle_time()));
+}
+
+#[test]
+fn test_follower() {
+    let mut leader: Friction<f64> = Friction::with_drag(0.01);
+    leader.set(0.0, 500.0);
+    let mut spring: Spring<f64> = Spring::with_constants(1.0, 200.0, 20.0);
+    spring.snap(-50.0);
+    let follower = Follower::new(leader, spring);
+    assert_eq!(follower.x(0.0), -50.0);
+    // Sampling at different rates (or out of order) gives the same motion.
+    let coarse: Vec<f64> = (0..30).map(|i| follower.x(i as f64 * 0.1)).collect();
+    let fine = follower.clone();
+    for i in 0..300 {
+        let x = fine.x(i as f64 * 0.01);
+        if i % 10 == 0 {
+            assert!((x - coarse[i / 10]).abs() < 1e-9);
+        }
+    }
+    assert!((follower.x(0.5) - coarse[5]).abs() < 1e-9);
+    // It catches up with the leader and stops with it.
+    assert!(follower.x(0.2) > -50.0);
+    let t = follower.settle_time();
+    assert!(t.is_finite());
+    assert!((follower.x(t) - leader.x(t)).abs() < 0.001);
+    assert_eq!(follower.final_position(), leader.final_position());
+}
+
+#[test]
+fn test_follower_invalid_step() {
+    let mut leader: Friction<f64> = Friction::with_drag(0.01);
+    leader.set(0.0, 500.0);
+    let mut spring: Spring<f64> = Spring::with_constants(1.0, 200.0, 20.0);
+    spring.snap(-50.0);
+    let mut follower = Follower::new(leader, spring);
+    follower.set_step(0.05);
+    let expected = follower.x(0.5);
+    // Steps that would never reach a time are ignored.
+    for &step in &[0.0, -0.01, f64::NAN, f64::INFINITY] {
+        follower.set_step(step);
+        assert_eq!(follower.x(0.5), expected);
+    }
+}
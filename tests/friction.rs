@@ -25,3 +25,37 @@ fn test_friction() {
     assert!(f.is_done(3.0));
     assert!(f.dx(3.0) < 0.1);
 }
+
+#[test]
+fn test_friction_final_position() {
+    let mut f: Friction = Friction::new(0.01);
+    f.set(10.0, 1000.0);
+    let t = f.settle_time();
+    assert!(!f.is_done(t - 0.01));
+    assert!(f.is_done(t + 0.01));
+    assert!((f.final_position() - f.x(100.0)).abs() < 0.01);
+}
+
+#[test]
+fn test_friction_acceleration() {
+    let mut f: Friction<f64> = Friction::with_drag(0.01);
+    f.set(0.0, 1000.0);
+    let h = 0.000001;
+    let estimate = (f.dx(0.5 + h) - f.dx(0.5 - h)) / (2.0 * h);
+    assert!((f.ddx(0.5) - estimate).abs() < 0.01);
+}
+
+#[test]
+fn test_friction_inverse() {
+    let mut f: Friction<f64> = Friction::with_drag(0.01);
+    let v = f.velocity_for_distance(-250.0);
+    assert!(v < 0.0);
+    f.set(40.0, v);
+    assert!((f.final_position() + 210.0).abs() < 1e-9);
+
+    let drag: f64 = Friction::drag_for_distance(800.0, 300.0);
+    let mut f = Friction::with_drag(drag);
+    f.set(0.0, 800.0);
+    assert!((f.final_position() - 300.0).abs() < 1e-9);
+    assert!(Friction::<f64>::drag_for_distance(800.0, -300.0).is_nan());
+}
@@ -1,4 +1,4 @@
-use gravitas::{Gravity, Simulation};
+use gravitas::{Bounce, DragGravity, Gravity, Simulation};
 
 #[test]
 fn test_gravity() {
@@ -15,3 +15,177 @@ fn test_gravity() {
     assert_eq!(g.dx(1.0), 4800.0);
     assert_eq!(g.dx(2.0), 9700.0);
 }
+
+#[test]
+fn test_gravity_settle_time() {
+    let mut g: Gravity = Gravity::new(1000.0);
+    g.set(0.0, -2000.0);
+    let t = g.settle_time();
+    assert!(!g.is_done(t - 0.01));
+    assert!(g.is_done(t + 0.01));
+    assert!((g.final_position() - 32000.0).abs() < 0.1);
+    assert!(Gravity::<f32>::new(0.0).settle_time().is_nan());
+}
+
+#[test]
+fn test_gravity_time_for_position() {
+    let mut g: Gravity = Gravity::new(100.0);
+    g.set(0.0, -100.0);
+    // Up to -50 at one second, then back down through zero at two seconds.
+    assert!((g.time_for_position(-50.0) - 1.0).abs() < 0.0001);
+    assert!((g.time_for_position(0.0) - 0.0).abs() < 0.0001);
+    assert!((g.time_for_position(10.0) - 2.0954451).abs() < 0.0001);
+    assert!(g.time_for_position(-60.0).is_nan());
+}
+
+#[test]
+fn test_bounce() {
+    let mut b: Bounce<f64> = Bounce::new(1000.0, 500.0, 0.5);
+    b.set(0.0, 0.0);
+    // Falls for one second, hitting the floor at 1000 per second.
+    assert!((b.impact_time(0) - 1.0).abs() < 1e-9);
+    assert!((b.dx(0.999_999) - 1000.0).abs() < 0.01);
+    assert_eq!(b.rebound_velocity(0), -500.0);
+    // The rebounds get slower until the tenth, which would be under a pixel per second.
+    assert_eq!(b.bounces(), 9);
+    assert_eq!(b.rebound_velocity(9), 0.0);
+    assert!(b.rebound_velocity(10).is_nan());
+    for i in 0..9 {
+        let (start, end) = (b.impact_time(i), b.impact_time(i + 1));
+        // Each flight takes 2v / a.
+        assert!((end - start - 2.0 * b.rebound_velocity(i).abs() / 1000.0).abs() < 1e-9);
+        // Starts and ends on the floor, moving at the rebound velocity.
+        assert!((b.x(start) - 500.0).abs() < 1e-6);
+        assert!((b.x(end - 1e-9) - 500.0).abs() < 1e-3);
+        assert!((b.dx(start) - b.rebound_velocity(i)).abs() < 1e-6);
+        // The top of the bounce is v * v / 2a above the floor.
+        let top = b.x((start + end) / 2.0);
+        let v = b.rebound_velocity(i);
+        assert!((500.0 - top - v * v / 2000.0).abs() < 1e-6);
+    }
+    assert_eq!(b.settle_time(), b.impact_time(9));
+    assert!(b.is_done(b.settle_time()) && !b.is_done(b.settle_time() - 1e-6));
+    assert_eq!(b.x(100.0), 500.0);
+    assert_eq!(b.dx(100.0), 0.0);
+    assert_eq!(b.final_position(), 500.0);
+    // The total time is a geometric series: 1 + 2 * 0.5 * (1 - 0.5^9) / 0.5.
+    assert!((b.settle_time() - (1.0 + 2.0 * (1.0 - 0.5f64.powi(9)))).abs() < 1e-9);
+
+    // Positions are found in the fall and then in the bounces.
+    assert!((b.time_for_position(125.0) - 0.5).abs() < 1e-9);
+    assert!((b.time_for_position(400.0) - 0.8f64.sqrt()).abs() < 1e-9);
+    assert!(b.time_for_position(600.0).is_nan());
+    assert!(b.time_for_position(-10.0).is_nan());
+    // Thrown down hard, the first bounce goes higher than it started.
+    b.set(450.0, 1000.0);
+    let t = b.time_for_position(400.0);
+    assert!(t > b.impact_time(0) && (b.x(t) - 400.0).abs() < 1e-6 && b.dx(t) < 0.0);
+}
+
+#[test]
+fn test_bounce_restitution() {
+    // Throwing upwards (negative acceleration) with no restitution comes to rest on impact.
+    let mut b: Bounce<f64> = Bounce::new(-100.0, 0.0, 0.0);
+    b.set(10.0, 10.0);
+    assert_eq!(b.bounces(), 0);
+    assert!((b.x(b.settle_time()) - 0.0).abs() < 1e-9);
+    assert!((b.settle_time() - b.impact_time(0)).abs() < 1e-12);
+    assert!((b.x(b.impact_time(0) - 1e-6)).abs() < 1e-3);
+
+    // A perfectly elastic bounce never stops.
+    b.set_restitution(1.0);
+    assert_eq!(b.bounces(), usize::MAX);
+    assert!(b.settle_time().is_nan());
+    assert!(!b.is_done(1000.0));
+    let (t, flight) = (b.impact_time(0), b.impact_time(1) - b.impact_time(0));
+    assert!((b.x(t + 0.3 * flight) - b.x(t + 100.3 * flight)).abs() < 1e-6);
+    assert!(b.time_for_position(1000.0).is_nan());
+}
+
+#[test]
+fn test_drag_gravity() {
+    let mut g: DragGravity<f64> = DragGravity::with_terminal_velocity(5000.0, 2000.0);
+    assert!((g.drag() - 2.5).abs() < 1e-12);
+    assert_eq!(g.terminal_velocity(), 2000.0);
+    g.set(10.0, -1000.0);
+    assert_eq!(g.x(0.0), 10.0);
+    assert_eq!(g.dx(0.0), -1000.0);
+    // It starts out accelerating like gravity, and approaches the terminal velocity.
+    assert!((g.ddx(0.0) - 7500.0).abs() < 1e-9);
+    assert!((g.dx(10.0) - 2000.0).abs() < 1e-6);
+    assert!(g.dx(10.0) < 2000.0);
+    for i in 1..40 {
+        let t = i as f64 * 0.05;
+        let h = 1e-5;
+        assert!((g.dx(t) - (g.x(t + h) - g.x(t - h)) / (2.0 * h)).abs() < 1e-3);
+        assert!((g.ddx(t) - (g.dx(t + h) - g.dx(t - h)) / (2.0 * h)).abs() < 1e-2);
+    }
+    // Thrown upwards it turns around, so positions on the way up and on the way down are found.
+    let turn = (3000.0f64 / 2000.0).ln() / 2.5;
+    assert!(g.dx(turn).abs() < 1e-9);
+    let top = g.x(turn);
+    let t = g.time_for_position(-50.0);
+    assert!(t < turn && (g.x(t) + 50.0).abs() < 0.01);
+    let t = g.time_for_position(500.0);
+    assert!(t > turn && (g.x(t) - 500.0).abs() < 0.01);
+    assert_eq!(g.time_for_position(10.0), 0.0);
+    assert!(g.time_for_position(top - 1.0).is_nan());
+
+    // It's done once it reaches the stop.
+    g.set_stop(5000.0);
+    let t = g.settle_time();
+    assert!(g.is_done(t) && !g.is_done(t - 0.001));
+    assert!((g.final_position() - 5000.0).abs() < 0.01);
+
+    // Without any acceleration the drag brings it to a stop before the stop region.
+    let mut g: DragGravity<f64> = DragGravity::new(0.0, 2.0);
+    g.set(0.0, 100.0);
+    assert!(g.time_for_position(60.0).is_nan());
+    assert!((g.x(g.time_for_position(40.0)) - 40.0).abs() < 0.01);
+    assert!(g.settle_time().is_nan());
+}
+
+#[test]
+fn test_gravity_bounds() {
+    // A lock screen thrown upwards, which falls back out of the bottom of an 800 pixel viewport.
+    let mut g: Gravity<f64> = Gravity::with_acceleration(1000.0);
+    g.set_bounds(-200.0, 800.0);
+    assert_eq!(g.bounds(), (-200.0, 800.0));
+    assert_eq!(g.stop(), 800.0);
+    g.set(0.0, -500.0);
+    // Peaks at -125 after half a second, then falls to 800: 0.5 * 1000 * t^2 = 925.
+    let (time, position, velocity) = g.exit().unwrap();
+    assert_eq!(position, 800.0);
+    let fall = (925.0f64 / 500.0).sqrt();
+    assert!((time - 0.5 - fall).abs() < 1e-9);
+    assert!((velocity - 1000.0 * fall).abs() < 1e-6);
+    assert!((g.x(time) - 800.0).abs() < 1e-9);
+    assert_eq!(g.settle_time(), time);
+    assert!(g.is_done(time + 1e-9) && !g.is_done(time - 1e-6));
+
+    // Thrown harder, it leaves through the top instead.
+    g.set(0.0, -1000.0);
+    let (time, position, velocity) = g.exit().unwrap();
+    assert_eq!(position, -200.0);
+    assert!((time - (1.0 - 0.6f64.sqrt())).abs() < 1e-9);
+    assert!((velocity + 1000.0 * 0.6f64.sqrt()).abs() < 1e-6);
+
+    // Starting outside of the bounds exits straight away, and without any acceleration it may never exit.
+    g.set(900.0, 10.0);
+    assert_eq!(g.exit(), Some((0.0, 900.0, 10.0)));
+    let mut g: Gravity<f64> = Gravity::with_acceleration(0.0);
+    g.set(0.0, 0.0);
+    assert_eq!(g.exit(), None);
+
+    // DragGravity has the same bounds.
+    let mut d: DragGravity<f64> = DragGravity::new(1000.0, 1.0);
+    d.set_bounds(-200.0, 800.0);
+    d.set(0.0, -500.0);
+    let (time, position, velocity) = d.exit().unwrap();
+    assert_eq!(position, 800.0);
+    assert!((d.x(time) - 800.0).abs() < 0.01);
+    assert_eq!(velocity, d.dx(time));
+    d.set_stop(500.0);
+    assert_eq!(d.bounds(), (-500.0, 500.0));
+    assert_eq!(d.stop(), 500.0);
+}
@@ -0,0 +1,81 @@
+use gravitas::{Friction, Gravity, Handoff, Simulation, Spring, Tolerance, Trigger};
+
+#[test]
+fn test_handoff_time() {
+    let mut g: Gravity = Gravity::new(100.0);
+    g.set(0.0, 0.0);
+    let h = Handoff::new(g, Spring::new(1.0, 90.0, 20.0), Trigger::Time(1.0));
+    assert_eq!(h.switch_time(), 1.0);
+    assert_eq!(h.x(0.5), g.x(0.5));
+    // The spring takes over with the gravity's position and velocity and pulls back to zero.
+    assert!((h.x(1.0) - 50.0).abs() < 0.001);
+    assert!((h.dx(1.0) - 100.0).abs() < 0.001);
+    assert!(!h.is_done(1.0));
+    assert!(h.is_done(10.0));
+    assert!(h.x(10.0).abs() < 0.01);
+}
+
+#[test]
+fn test_handoff_position_velocity() {
+    let mut f: Friction = Friction::new(0.01);
+    f.set(0.0, 1000.0);
+    let tolerance = Tolerance::new(0.001, 0.001, 0.000001);
+    let h = Handoff::with_tolerance(
+        f,
+        Spring::new(1.0, 90.0, 20.0),
+        Trigger::Position(100.0),
+        tolerance,
+    );
+    let t = h.switch_time();
+    assert!((t - f.time_for_position(100.0)).abs() < 0.001);
+    assert!((h.x(t) - 100.0).abs() < 0.01);
+    assert!((h.dx(t) - f.dx(t)).abs() < 0.01);
+
+    let h = Handoff::with_tolerance(
+        f,
+        Spring::new(1.0, 90.0, 20.0),
+        Trigger::Velocity(500.0),
+        tolerance,
+    );
+    assert!((h.first().dx(h.switch_time()) - 500.0).abs() < 0.01);
+
+    // Friction never gets to 1000.0, so it never hands off.
+    let h = Handoff::new(f, Spring::new(1.0, 90.0, 20.0), Trigger::Position(1000.0));
+    assert!(h.switch_time().is_nan());
+    assert_eq!(h.x(1.0), f.x(1.0));
+}
+
+#[test]
+fn test_handoff_speeding_up() {
+    // Dropped from rest, gravity speeds up to the trigger velocity after half a second.
+    let mut g: Gravity<f64> = Gravity::with_acceleration(1000.0);
+    g.set(0.0, 0.0);
+    let tolerance = Tolerance::new(0.001, 0.001, 0.000001);
+    let spring: Spring<f64> = Spring::with_constants(1.0, 90.0, 20.0);
+    let h = Handoff::with_tolerance(g, spring, Trigger::Velocity(500.0), tolerance);
+    let t = h.switch_time();
+    assert!((t - 0.5).abs() < 0.00001);
+    assert!((h.x(t) - 125.0).abs() < 0.01);
+    assert!((h.dx(t) - 500.0).abs() < 0.01);
+}
+
+#[test]
+fn test_handoff_set_trigger() {
+    // Set up again in the way `Scroll` does: a fling which hands off to a spring at an extent.
+    let mut f: Friction<f64> = Friction::with_drag(0.01);
+    f.set(0.0, 1000.0);
+    let spring: Spring<f64> = Spring::with_constants(1.0, 90.0, 20.0);
+    let mut h = Handoff::new(f, spring, Trigger::Time(f64::NAN));
+    assert!(h.switch_time().is_nan());
+    h.second_mut().snap(100.0);
+    let t = h.first().time_for_position(100.0);
+    h.set_trigger(Trigger::Time(t));
+    assert_eq!(h.switch_time(), t);
+    assert!((h.x(t) - 100.0).abs() < 1e-9);
+    assert_eq!(h.dx(t), f.dx(t));
+    assert_eq!(h.final_position(), 100.0);
+    // Positions before the switch come from the fling, and after it from the spring's overshoot.
+    assert_eq!(h.time_for_position(50.0), f.time_for_position(50.0));
+    let overshoot = h.time_for_position(110.0);
+    assert!(overshoot > t && (h.x(overshoot) - 110.0).abs() < 0.01);
+}
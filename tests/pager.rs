@@ -0,0 +1,101 @@
+use gravitas::{Deceleration, Pager, PagerSnapPoint, RubberBand, Simulation, SpringLimit};
+
+fn snap_points() -> [PagerSnapPoint; 3] {
+    [
+        PagerSnapPoint {
+            value: 0.0,
+            snap: true,
+        },
+        PagerSnapPoint {
+            value: -200.0,
+            snap: true,
+        },
+        PagerSnapPoint {
+            value: -100.0,
+            snap: true,
+        },
+    ]
+}
+
+#[test]
+fn test_pager_storage() {
+    // Snap points in an array don't need to be sorted, and give the same result as a Vec.
+    let mut array = Pager::with_snap_points(snap_points());
+    array.set(-60.0, -50.0);
+    assert!(array.is_done(5.0));
+    assert!((array.x(5.0) + 100.0).abs() < 0.01);
+
+    #[cfg(feature = "std")]
+    {
+        let mut vec: Pager = Pager::new(&snap_points());
+        vec.set(-60.0, -50.0);
+        for i in 0..10 {
+            let t = i as f32 * 0.1;
+            assert_eq!(vec.x(t), array.x(t));
+        }
+    }
+}
+
+#[test]
+fn test_pager_spring_limit() {
+    // A fast fling towards a snap point overshoots it, unless the spring is clamped.
+    let mut p = Pager::with_snap_points(snap_points());
+    p.set(-20.0, 3000.0);
+    let max = (0..200)
+        .map(|i| p.x(i as f32 * 0.01))
+        .fold(f32::MIN, f32::max);
+    assert!(max > 1.0);
+    p.set_spring_limit(SpringLimit::ClampOvershoot);
+    p.set(-20.0, 3000.0);
+    let max = (0..200)
+        .map(|i| p.x(i as f32 * 0.01))
+        .fold(f32::MIN, f32::max);
+    assert!(max <= 0.01);
+    assert!(p.is_done(p.settle_time()));
+    assert_eq!(p.final_position(), 0.0);
+}
+
+#[test]
+fn test_pager_deceleration() {
+    // A fast fling picks the snap point closest to the projected position.
+    let d: Deceleration = Deceleration::fast();
+    let mut p = Pager::with_snap_points(snap_points()).with_fling(d);
+    p.set(-20.0, -9000.0);
+    assert!(-20.0 + d.project(-9000.0) < -100.0);
+    assert_eq!(p.final_position(), -100.0);
+    p.set(-20.0, -100.0);
+    assert!(-20.0 + d.project(-100.0) > -50.0);
+    assert_eq!(p.final_position(), 0.0);
+}
+
+#[test]
+fn test_pager_land_on_snap_points() {
+    // The fling's velocity is adjusted so that it stops on the snap point, without a spring.
+    let mut p = Pager::with_snap_points(snap_points());
+    p.set_land_on_snap_points(true);
+    p.set(-20.0, -250.0);
+    assert!((p.final_position() + 100.0).abs() < 0.01);
+    assert!((p.x(p.settle_time()) + 100.0).abs() < 1.0);
+    assert!(p.dx(0.0) < -150.0 && p.dx(0.0) > -400.0);
+    for i in 1..100 {
+        // Never passes the snap point.
+        assert!(p.x(i as f32 * 0.05) >= -100.0);
+    }
+    // Heading away from the closest snap point still uses the spring.
+    p.set(-90.0, 5.0);
+    assert_eq!(p.final_position(), -100.0);
+    assert!((p.dx(0.0) - 5.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_pager_rubber_band() {
+    let band = RubberBand::Log { scale: 50.0 };
+    let p = Pager::with_snap_points(snap_points());
+    // The snap points go from -200 to 0, and between them the content follows the finger.
+    assert_eq!(p.rubber_band(&band, -150.0), -150.0);
+    assert!((p.rubber_band(&band, 30.0) - band.damp(30.0)).abs() < 1e-6);
+    assert!((p.rubber_band(&band, -230.0) + 200.0 + band.damp(30.0)).abs() < 1e-4);
+    assert!((p.rubber_band_offset(&band, p.rubber_band(&band, -230.0)) + 230.0).abs() < 1e-3);
+    assert_eq!(p.rubber_band_velocity(&band, -150.0, 40.0), 40.0);
+    assert!((p.rubber_band_velocity(&band, 50.0, 40.0) - 20.0).abs() < 1e-6);
+}
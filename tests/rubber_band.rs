@@ -0,0 +1,60 @@
+use gravitas::{RubberBand, Scroll, Simulation};
+
+#[test]
+fn test_rubber_band_curves() {
+    let bands: [RubberBand<f64>; 3] = [
+        RubberBand::ios(400.0),
+        RubberBand::Linear { factor: 0.5 },
+        RubberBand::Log { scale: 100.0 },
+    ];
+    for band in bands.iter() {
+        assert_eq!(band.damp(0.0), 0.0);
+        for i in 1..50 {
+            let x = i as f64 * 20.0;
+            let y = band.damp(x);
+            // Damped, symmetric and invertible.
+            assert!(y > 0.0 && y <= x);
+            assert_eq!(band.damp(-x), -y);
+            assert!((band.undamp(y) - x).abs() < 1e-6 * x);
+            assert!((band.undamp(-y) + x).abs() < 1e-6 * x);
+            // The slope is the derivative.
+            let h = 1e-4;
+            let estimate = (band.damp(x + h) - band.damp(x - h)) / (2.0 * h);
+            assert!((band.slope(x) - estimate).abs() < 1e-6);
+            assert_eq!(band.slope(-x), band.slope(x));
+        }
+    }
+    // UIKit's curve never goes past the dimension.
+    let ios: RubberBand<f64> = RubberBand::ios(400.0);
+    assert!((ios.damp(100.0) - (1.0 - 1.0 / (100.0 * 0.55 / 400.0 + 1.0)) * 400.0).abs() < 1e-9);
+    assert!(ios.damp(1e9) < 400.0);
+    assert_eq!(ios.undamp(400.0), f64::INFINITY);
+    assert_eq!(ios.slope(0.0), 0.55);
+}
+
+#[test]
+fn test_scroll_rubber_band() {
+    let band: RubberBand<f64> = RubberBand::ios(600.0);
+    let mut s: Scroll<f64> = Scroll::with_extent(1000.0);
+    // Within the bounds the content follows the finger.
+    assert_eq!(s.rubber_band(&band, -300.0), -300.0);
+    assert_eq!(s.rubber_band_velocity(&band, -300.0, 50.0), 50.0);
+    // Beyond them it's damped.
+    let top = s.rubber_band(&band, 100.0);
+    assert!((top - band.damp(100.0)).abs() < 1e-9);
+    let bottom = s.rubber_band(&band, -1100.0);
+    assert!((bottom + 1000.0 + band.damp(100.0)).abs() < 1e-9);
+    assert!((s.rubber_band_offset(&band, bottom) + 1100.0).abs() < 1e-9);
+    assert_eq!(s.rubber_band_offset(&band, -500.0), -500.0);
+
+    // The velocity at release matches how fast the content was moving.
+    let (offset, v, h) = (150.0, 300.0, 1e-5);
+    let moving =
+        (s.rubber_band(&band, offset + v * h) - s.rubber_band(&band, offset - v * h)) / (2.0 * h);
+    let velocity = s.rubber_band_velocity(&band, offset, v);
+    assert!((velocity - moving).abs() < 1e-4);
+    assert!(velocity < v);
+    s.set(s.rubber_band(&band, offset), velocity);
+    assert!((s.dx(0.0) - velocity).abs() < 1e-9);
+    assert_eq!(s.final_position(), 0.0);
+}
@@ -0,0 +1,128 @@
+use gravitas::{Deceleration, Friction, Scroll, ScrollConvention, Simulation, Tolerance};
+
+#[test]
+fn test_scroll_final_position() {
+    let mut s: Scroll = Scroll::new(1000.0);
+    s.set(-500.0, -100.0);
+    assert!((s.final_position() - s.x(100.0)).abs() < 0.01);
+    s.set(-500.0, -10000.0);
+    assert_eq!(s.final_position(), -1000.0);
+    assert!(s.is_done(s.settle_time()));
+    assert!(!s.is_done(s.settle_time() - 0.2));
+}
+
+#[test]
+fn test_scroll_tolerance() {
+    // Scroll through a unit range, like scrolling a list of progress values.
+    let mut s: Scroll<f64> = Scroll::with_extent(1.0);
+    s.set_tolerance(Tolerance::new(0.0001, 0.0001, 0.0001));
+    s.set(-0.5, -0.2);
+    let t = s.settle_time();
+    assert!(s.is_done(t));
+    assert!(!s.is_done(t * 0.5));
+    assert!((s.x(t) - s.final_position()).abs() < 0.001);
+
+    // With the default tolerance the friction stops immediately.
+    let mut s: Scroll<f64> = Scroll::with_extent(1.0);
+    s.set(-0.5, -0.2);
+    assert!(s.is_done(0.0));
+}
+
+#[test]
+fn test_scroll_acceleration() {
+    let mut s: Scroll<f64> = Scroll::with_extent(1000.0);
+    s.set(-500.0, -10000.0);
+    // Friction slows the scroll down, and then the spring pulls it back from the overscroll.
+    assert!(s.ddx(0.0) > 0.0);
+    for i in 0..40 {
+        let t = i as f64 * 0.05 + 0.01;
+        let h = 0.000001;
+        let estimate = (s.dx(t + h) - s.dx(t - h)) / (2.0 * h);
+        assert!((s.ddx(t) - estimate).abs() < 0.1);
+    }
+}
+
+#[test]
+fn test_scroll_time_for_position() {
+    let mut s: Scroll<f64> = Scroll::with_extent(1000.0);
+    s.set(-500.0, -10000.0);
+    // Crossing during the friction.
+    let t = s.time_for_position(-800.0);
+    assert!((s.x(t) + 800.0).abs() < 0.01);
+    // Into the overscroll with the spring, and then back again.
+    let into = s.time_for_position(-1010.0);
+    assert!((s.x(into) + 1010.0).abs() < 0.01);
+    assert!(s.dx(into) < 0.0);
+    let t = s.time_for_position(-1005.0);
+    assert!(t < into);
+    assert!(s.time_for_position(0.0).is_nan());
+}
+
+#[test]
+fn test_scroll_deceleration() {
+    // With UIKit's deceleration a fling stops where `project` says it will.
+    let d: Deceleration = Deceleration::normal();
+    let mut s = Scroll::new(10000.0).with_fling(d);
+    s.set(-500.0, -1500.0);
+    assert!((s.final_position() - (-500.0 + d.project(-1500.0))).abs() < 0.01);
+    assert!(s.is_done(s.settle_time()));
+    // And it still springs back from the ends.
+    let mut s = Scroll::new(1000.0).with_fling(Deceleration::fast());
+    s.set(-500.0, -10000.0);
+    assert_eq!(s.final_position(), -1000.0);
+    assert!(s.time_for_position(-1000.0) > 0.0);
+    assert!(s.x(0.2) < -1000.0);
+}
+
+#[test]
+fn test_scroll_range() {
+    // Positive offsets from an inset of 50 to 1050.
+    let mut s: Scroll<f64> = Scroll::with_range(50.0, 1050.0);
+    assert_eq!(s.convention(), ScrollConvention::Positive);
+    assert_eq!(s.bounds(), (50.0, 1050.0));
+    assert_eq!(s.extent(), 1000.0);
+    // Scrolling forward with a positive velocity stops at the maximum.
+    s.set(500.0, 10000.0);
+    assert_eq!(s.final_position(), 1050.0);
+    assert!(s.x(0.2) > 1050.0);
+    // Overscrolled past the inset, it springs back.
+    s.set(20.0, 0.0);
+    assert_eq!(s.final_position(), 50.0);
+    // A gentle fling stays in the range.
+    s.set(500.0, 100.0);
+    assert!(s.final_position() > 500.0 && s.final_position() < 1050.0);
+
+    // The same range with negative positions mirrors it.
+    let mut n: Scroll<f64> =
+        Scroll::with_range(50.0, 1050.0).with_convention(ScrollConvention::Negative);
+    assert_eq!(n.bounds(), (-1050.0, -50.0));
+    n.set(-500.0, -10000.0);
+    s.set(500.0, 10000.0);
+    for i in 0..20 {
+        let t = i as f64 * 0.1;
+        assert!((n.x(t) + s.x(t)).abs() < 1e-9);
+    }
+
+    // Scroll::new is the range from zero to the extent, with negative positions.
+    let mut old: Scroll<f64> = Scroll::with_extent(1000.0);
+    let mut new: Scroll<f64> =
+        Scroll::with_range(0.0, 1000.0).with_convention(ScrollConvention::Negative);
+    assert_eq!(old.bounds(), (-1000.0, 0.0));
+    assert_eq!(old.range(), (0.0, 1000.0));
+    old.set(-900.0, -3000.0);
+    new.set(-900.0, -3000.0);
+    assert_eq!(old.x(0.3), new.x(0.3));
+}
+
+#[test]
+fn test_scroll_time_for_extent() {
+    // The scroll reaches its extent during the friction, and the spring starts there.
+    let mut s: Scroll<f64> = Scroll::with_extent(1000.0);
+    s.set(-500.0, 3000.0);
+    let mut f: Friction<f64> = Friction::with_drag(0.01);
+    f.set(-500.0, 3000.0);
+    let t = f.time_for_position(0.0);
+    assert!(t > 0.0);
+    assert!((s.time_for_position(0.0) - t).abs() < 1e-9);
+    assert!(s.x(t + 0.05) > 0.0);
+}
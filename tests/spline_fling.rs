@@ -0,0 +1,103 @@
+use gravitas::{Scroll, Simulation, SplineFling};
+
+/// Android's OverScroller builds a table of the spline, and interpolates it.
+fn android_spline_table() -> Vec<f64> {
+    let (inflexion, start_tension, end_tension) = (0.35, 0.5, 1.0);
+    let p1 = start_tension * inflexion;
+    let p2 = 1.0 - end_tension * (1.0 - inflexion);
+    let samples = 100;
+    let mut table = vec![];
+    let mut x_min = 0.0;
+    for i in 0..samples {
+        let alpha = i as f64 / samples as f64;
+        let mut x_max = 1.0;
+        let (mut x, mut coef);
+        loop {
+            x = x_min + (x_max - x_min) / 2.0;
+            coef = 3.0 * x * (1.0 - x);
+            let tx = coef * ((1.0 - x) * p1 + x * p2) + x * x * x;
+            if (tx - alpha).abs() < 1e-5 {
+                break;
+            }
+            if tx > alpha {
+                x_max = x;
+            } else {
+                x_min = x;
+            }
+        }
+        table.push(coef * ((1.0 - x) * start_tension + x) + x * x * x);
+    }
+    table.push(1.0);
+    table
+}
+
+#[test]
+fn test_spline_fling() {
+    let density = 2.0;
+    let mut f: SplineFling<f64> = SplineFling::new(density);
+    f.set(100.0, 3000.0);
+    // OverScroller's distance and duration.
+    let physical = 9.80665 * 39.37 * density * 160.0 * 0.84;
+    let deceleration = (0.35 * 3000.0 / (0.015 * physical)).ln();
+    let rate = 0.78f64.ln() / 0.9f64.ln();
+    let duration = (deceleration / (rate - 1.0)).exp();
+    let distance = 0.015 * physical * (rate / (rate - 1.0) * deceleration).exp();
+    assert!((f.settle_time() - duration).abs() < 1e-9);
+    assert!((f.final_position() - 100.0 - distance).abs() < 1e-6);
+    assert!((f.distance(-3000.0) - distance).abs() < 1e-6);
+
+    // The curve matches Android's table.
+    let table = android_spline_table();
+    for i in 0..50 {
+        let t = i as f64 / 50.0;
+        let index = (t * 100.0) as usize;
+        let (t_inf, t_sup) = (index as f64 / 100.0, (index + 1) as f64 / 100.0);
+        let coef = table[index] + (t - t_inf) / (t_sup - t_inf) * (table[index + 1] - table[index]);
+        assert!((f.x(t * duration) - 100.0 - coef * distance).abs() < 0.01 * distance);
+    }
+    assert_eq!(f.x(0.0), 100.0);
+    assert_eq!(f.x(duration), f.final_position());
+    assert!(f.is_done(duration) && !f.is_done(duration * 0.99));
+
+    // The velocity and acceleration are the derivatives of the position.
+    for i in 1..20 {
+        let t = i as f64 / 20.0 * duration;
+        let h = 1e-4;
+        assert!((f.dx(t) - (f.x(t + h) - f.x(t - h)) / (2.0 * h)).abs() < 0.01);
+        assert!((f.ddx(t) - (f.dx(t + h) - f.dx(t - h)) / (2.0 * h)).abs() < 1.0);
+    }
+    let t = f.time_for_position(300.0);
+    assert!((f.x(t) - 300.0).abs() < 1e-6);
+    assert!(f.time_for_position(50.0).is_nan());
+    assert!(f.time_for_position(f.final_position() + 1.0).is_nan());
+}
+
+#[test]
+fn test_scroll_spline_fling() {
+    let mut s = Scroll::new(5000.0).with_fling(SplineFling::new(1.0));
+    s.set(-1000.0, -2000.0);
+    let mut f = SplineFling::new(1.0);
+    f.set(-1000.0, -2000.0);
+    assert_eq!(s.final_position(), f.final_position());
+    assert_eq!(s.x(0.1), f.x(0.1));
+    // Flinging past the end bounces back.
+    s.set(-4900.0, -5000.0);
+    assert_eq!(s.final_position(), -5000.0);
+    assert!(s.x(0.3) < -5000.0);
+    assert!(s.is_done(s.settle_time()));
+}
+
+#[test]
+fn test_spline_fling_inverse() {
+    let mut f: SplineFling<f64> = SplineFling::new(3.0);
+    let v = f.velocity_for_distance(-640.0);
+    assert!((f.distance(v) - 640.0).abs() < 1e-6);
+    f.set(0.0, v);
+    assert!((f.final_position() + 640.0).abs() < 1e-6);
+    assert_eq!(f.velocity_for_distance(0.0), 0.0);
+
+    let friction = f.friction_for_distance(5000.0, 2500.0);
+    f.set_friction(friction);
+    assert!((f.distance(5000.0) - 2500.0).abs() < 1e-6);
+    assert!(f.friction_for_distance(5000.0, -2500.0).is_nan());
+}
@@ -1,4 +1,4 @@
-use gravitas::{Simulation, Spring};
+use gravitas::{HangingSpring, Resume, Simulation, Spring, SpringLimit, SpringSpec, Tolerance};
 
 #[test]
 fn test_snapped() {
@@ -7,3 +7,504 @@ fn test_snapped() {
     assert_eq!(s.dx(0.0), 0.0);
     assert!(s.is_done(0.0));
 }
+
+#[test]
+fn test_f64_matches_f32() {
+    let mut s32: Spring = Spring::new(1.0, 90.0, 20.0);
+    let mut s64 = Spring::<f64>::with_constants(1.0, 90.0, 20.0);
+    s32.snap(100.0);
+    s32.set(0.0, 500.0, 0.0);
+    s64.snap(100.0);
+    s64.set(0.0, 500.0, 0.0);
+    for i in 0..20 {
+        let t = i as f32 * 0.05;
+        assert!((s32.x(t) as f64 - s64.x(t as f64)).abs() < 0.01);
+        assert!((s32.dx(t) as f64 - s64.dx(t as f64)).abs() < 0.1);
+    }
+}
+
+#[test]
+fn test_settle_time() {
+    // Underdamped, critically damped and overdamped.
+    for &(k, c) in &[(90.0, 10.0), (100.0, 20.0), (100.0, 40.0)] {
+        let mut s: Spring<f64> = Spring::with_constants(1.0, k, c);
+        s.snap(100.0);
+        s.set(0.0, 500.0, 0.0);
+        let t = s.settle_time();
+        assert!(t.is_finite() && t > 0.0);
+        assert!(s.is_done(t));
+        // The settle time is from the envelope, so it shouldn't be much later than is_done.
+        assert!(!s.is_done(t * 0.75));
+        assert_eq!(s.final_position(), 0.0);
+    }
+}
+
+#[test]
+fn test_tolerance() {
+    // Animating opacity from 0 to 1 needs a finer tolerance than the default.
+    let mut s: Spring = Spring::new(1.0, 90.0, 20.0);
+    s.set_tolerance(Tolerance::new(0.00001, 0.0001, 0.001));
+    s.set(1.0, 0.0, 0.0);
+    let t = s.settle_time();
+    assert!(s.is_done(t));
+    assert!((s.x(t) - 1.0).abs() < 0.00001);
+}
+
+#[test]
+fn test_acceleration() {
+    for &(k, c) in &[(90.0, 10.0), (100.0, 20.0), (100.0, 40.0)] {
+        let mut s: Spring<f64> = Spring::with_constants(1.0, k, c);
+        s.snap(100.0);
+        s.set(0.0, 500.0, 0.0);
+        for i in 0..20 {
+            let t = i as f64 * 0.05;
+            let h = 0.000001;
+            let estimate = (s.dx(t + h) - s.dx(t - h)) / (2.0 * h);
+            assert!((s.ddx(t) - estimate).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_crossings() {
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 90.0, 4.0);
+    s.snap(100.0);
+    s.set(0.0, 0.0, 0.0);
+    // Count the crossings of a few positions by sampling, and check the iterator finds the same ones.
+    for &p in &[50.0, 0.0, -10.0, 100.0] {
+        let mut sampled = vec![];
+        let step = 0.0001;
+        for i in 1..50000 {
+            let (a, b) = ((i - 1) as f64 * step, i as f64 * step);
+            if (s.x(a) - p < 0.0) != (s.x(b) - p < 0.0) && (s.x(b) - p).abs() > 0.0 {
+                sampled.push(b);
+            }
+        }
+        let crossings: Vec<f64> = s.crossings(p).collect();
+        // Crossings of the end are reported until the oscillation is below the tolerance, which is
+        // longer than we sampled for.
+        assert!(crossings.len() >= sampled.len());
+        for (c, s) in crossings.iter().zip(sampled.iter()) {
+            assert!((c - s).abs() < 0.001);
+        }
+        for c in &crossings {
+            assert!((s.x(*c) - p).abs() < 0.01);
+        }
+    }
+    assert_eq!(s.time_for_position(100.0), 0.0);
+    assert!(s.time_for_position(200.0).is_nan());
+    assert!((s.time_for_position(-10.0) - s.crossings(-10.0).next().unwrap()).abs() < 1e-9);
+}
+
+#[test]
+fn test_crossings_overdamped() {
+    // Moving away from the end before turning around gives two crossings of the start position.
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 100.0, 40.0);
+    s.snap(10.0);
+    s.set(0.0, 200.0, 0.0);
+    let crossings: Vec<f64> = s.crossings(12.0).collect();
+    assert_eq!(crossings.len(), 2);
+    for c in &crossings {
+        assert!((s.x(*c) - 12.0).abs() < 0.001);
+    }
+    assert!(s.crossings(-1.0).next().is_none());
+}
+
+/// Integrate `m * x'' + c * x' + k * (x - end) = 0` numerically with fourth order Runge-Kutta.
+fn integrate(m: f64, k: f64, c: f64, end: f64, x: f64, v: f64, time: f64) -> (f64, f64) {
+    let acceleration = |x: f64, v: f64| -(c * v + k * (x - end)) / m;
+    let steps = 20000;
+    let h = time / steps as f64;
+    let (mut x, mut v) = (x, v);
+    for _ in 0..steps {
+        let (k1x, k1v) = (v, acceleration(x, v));
+        let (k2x, k2v) = (
+            v + 0.5 * h * k1v,
+            acceleration(x + 0.5 * h * k1x, v + 0.5 * h * k1v),
+        );
+        let (k3x, k3v) = (
+            v + 0.5 * h * k2v,
+            acceleration(x + 0.5 * h * k2x, v + 0.5 * h * k2v),
+        );
+        let (k4x, k4v) = (v + h * k3v, acceleration(x + h * k3x, v + h * k3v));
+        x += h / 6.0 * (k1x + 2.0 * k2x + 2.0 * k3x + k4x);
+        v += h / 6.0 * (k1v + 2.0 * k2v + 2.0 * k3v + k4v);
+    }
+    (x, v)
+}
+
+#[test]
+fn test_against_integrator() {
+    // (mass, spring constant, damping) for overdamped, critically damped and underdamped springs,
+    // with masses other than one.
+    let springs = [
+        (2.0, 50.0, 40.0),
+        (0.5, 80.0, 12.0),
+        (2.0, 200.0, 40.0),
+        (3.0, 300.0, 10.0),
+        (0.25, 100.0, 1.0),
+    ];
+    // (start, velocity), including starting at the end with only a velocity.
+    let starts = [(100.0, 0.0), (100.0, -300.0), (0.0, 500.0), (-20.0, 1000.0)];
+    for &(m, k, c) in &springs {
+        for &(x, v) in &starts {
+            let mut s: Spring<f64> = Spring::with_constants(m, k, c);
+            s.snap(x);
+            s.set(0.0, v, 0.0);
+            for &t in &[0.0, 0.05, 0.2, 0.5, 1.0] {
+                let (ix, iv) = integrate(m, k, c, 0.0, x, v, t);
+                assert!((s.x(t) - ix).abs() < 0.001, "x m={} k={} c={}", m, k, c);
+                assert!((s.dx(t) - iv).abs() < 0.01, "dx m={} k={} c={}", m, k, c);
+                // The acceleration should satisfy the equation of motion too.
+                let a = -(c * s.dx(t) + k * s.x(t)) / m;
+                assert!((s.ddx(t) - a).abs() < 0.01, "ddx m={} k={} c={}", m, k, c);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_critical_continuity() {
+    // Approaching critical damping from either side should converge on the critically damped
+    // solution.
+    let (m, k): (f64, f64) = (2.0, 200.0);
+    let critical = 2.0 * (m * k).sqrt();
+    let mut s: Spring<f64> = Spring::with_constants(m, k, critical);
+    s.snap(100.0);
+    s.set(0.0, 250.0, 0.0);
+    for &ratio in &[1e-3, 1e-5, 1e-7, 1e-9] {
+        for &sign in &[-1.0, 1.0] {
+            let damping = critical * (1.0 + sign * ratio);
+            let mut near: Spring<f64> = Spring::with_constants(m, k, damping);
+            near.snap(100.0);
+            near.set(0.0, 250.0, 0.0);
+            let mut near32: Spring = Spring::new(m as f32, k as f32, damping as f32);
+            near32.snap(100.0);
+            near32.set(0.0, 250.0, 0.0);
+            for i in 0..20 {
+                let t = i as f64 * 0.05;
+                let limit = 100.0 * ratio.max(1e-6) * 10.0;
+                assert!((near.x(t) - s.x(t)).abs() < limit, "ratio {}", ratio);
+                // f32 shouldn't lose precision close to critical damping.
+                assert!(
+                    (near32.x(t as f32) as f64 - near.x(t)).abs() < 0.01,
+                    "ratio {}",
+                    ratio
+                );
+                assert!(
+                    (near32.dx(t as f32) as f64 - near.dx(t)).abs() < 0.1,
+                    "ratio {}",
+                    ratio
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_perceptual_parameters() {
+    let s: Spring<f64> = Spring::with_duration(0.5, 0.3);
+    assert!((s.duration() - 0.5).abs() < 1e-9);
+    assert!((s.bounce() - 0.3).abs() < 1e-9);
+    assert!((s.damping_ratio() - 0.7).abs() < 1e-9);
+    let s: Spring<f64> = Spring::with_duration(0.5, -0.5);
+    assert!((s.bounce() + 0.5).abs() < 1e-9);
+    assert!((s.damping_ratio() - 2.0).abs() < 1e-9);
+    let s: Spring<f64> = Spring::with_response(0.55, 0.825);
+    assert!((s.response() - 0.55).abs() < 1e-9);
+    assert!((s.damping_ratio() - 0.825).abs() < 1e-9);
+    // SpringForce.STIFFNESS_MEDIUM and DAMPING_RATIO_MEDIUM_BOUNCY.
+    let s: Spring<f64> = Spring::with_stiffness(1500.0, 0.5);
+    assert_eq!(s.mass(), 1.0);
+    assert!((s.stiffness() - 1500.0).abs() < 1e-9);
+    assert!((s.damping_ratio() - 0.5).abs() < 1e-9);
+    // The same parameters are reported for springs with other masses.
+    let s: Spring<f64> = Spring::with_constants(2.0, 200.0, 40.0);
+    assert!((s.damping_ratio() - 1.0).abs() < 1e-9);
+    assert!((s.stiffness() - 100.0).abs() < 1e-9);
+    assert_eq!(s.bounce(), 0.0);
+}
+
+#[test]
+fn test_spec() {
+    // Overshoot and a time.
+    let s: Spring<f64> = SpringSpec::new()
+        .overshoot(0.05)
+        .peak_time(0.3)
+        .build()
+        .unwrap();
+    let metrics = s.metrics(0.02);
+    assert!((metrics.overshoot - 0.05).abs() < 1e-6);
+    assert!((metrics.peak_time - 0.3).abs() < 1e-6);
+    // Two times, with a heavier spring.
+    let s: Spring<f64> = SpringSpec::new()
+        .mass(3.0)
+        .rise_time(0.1)
+        .settling_time(0.5, 0.01)
+        .build()
+        .unwrap();
+    assert_eq!(s.mass(), 3.0);
+    let metrics = s.metrics(0.01);
+    assert!((metrics.rise_time - 0.1).abs() < 1e-4);
+    assert!((metrics.settling_time - 0.5).abs() < 1e-4);
+    // One time gives a critically damped spring.
+    let s: Spring<f64> = SpringSpec::new().settling_time(0.4, 0.02).build().unwrap();
+    assert!((s.damping_ratio() - 1.0).abs() < 1e-9);
+    let metrics = s.metrics(0.02);
+    assert_eq!(metrics.overshoot, 0.0);
+    assert!(metrics.peak_time.is_infinite());
+    assert!((metrics.settling_time - 0.4).abs() < 1e-4);
+    // Check the settling time against sampling.
+    let mut step = s;
+    step.snap(1.0);
+    step.set(0.0, 0.0, 0.0);
+    assert!(step.x(0.399) > 0.02 && step.x(0.401) < 0.02);
+    // The settling time jumps down as the damping ratio passes about 0.78, because the first overshoot
+    // stops leaving the tolerance, and no spring has a rise time half of its settling time.
+    assert!(SpringSpec::<f64>::new()
+        .rise_time(0.2)
+        .settling_time(0.4, 0.02)
+        .build()
+        .is_none());
+    let s: Spring<f64> = SpringSpec::new()
+        .rise_time(0.3)
+        .settling_time(0.5, 0.02)
+        .build()
+        .unwrap();
+    let metrics = s.metrics(0.02);
+    assert!((metrics.rise_time - 0.3).abs() < 1e-3);
+    assert!((metrics.settling_time - 0.5).abs() < 1e-3);
+    // Too few or too many targets.
+    assert!(SpringSpec::<f64>::new().overshoot(0.1).build().is_none());
+    assert!(SpringSpec::<f64>::new()
+        .overshoot(0.1)
+        .peak_time(0.1)
+        .rise_time(0.1)
+        .build()
+        .is_none());
+}
+
+#[test]
+fn test_limited_metrics() {
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 90.0, 4.0);
+    let free = s.metrics(0.02);
+    assert!(free.overshoot > 0.1);
+    // A clamped spring never overshoots, and settles once it first gets within the tolerance.
+    s.set_limit(SpringLimit::ClampOvershoot);
+    let clamped = s.metrics(0.02);
+    assert_eq!(clamped.overshoot, 0.0);
+    assert!(clamped.peak_time.is_infinite());
+    assert_eq!(clamped.rise_time, free.rise_time);
+    let mut step = s;
+    step.snap(1.0);
+    step.set(0.0, 0.0, 0.0);
+    assert!((clamped.settling_time - step.time_for_position(0.02)).abs() < 1e-6);
+    assert!(clamped.settling_time < free.settling_time);
+    // With one bounce it overshoots as before, but settles sooner.
+    s.set_limit(SpringLimit::MaxBounces(1));
+    let bounced = s.metrics(0.02);
+    assert_eq!(bounced.overshoot, free.overshoot);
+    assert_eq!(bounced.peak_time, free.peak_time);
+    assert!(bounced.settling_time > clamped.settling_time);
+    assert!(bounced.settling_time < free.settling_time);
+}
+
+#[test]
+fn test_clamp_overshoot() {
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 90.0, 4.0);
+    s.set_limit(SpringLimit::ClampOvershoot);
+    s.snap(100.0);
+    s.set(0.0, 0.0, 0.0);
+    let first = s.time_for_position(0.0);
+    assert!(first > 0.0);
+    assert!(s.is_done(first + 0.001));
+    assert!((s.settle_time() - first).abs() < 0.001);
+    for i in 0..200 {
+        let t = i as f64 * 0.01;
+        assert!(s.x(t) >= -0.001);
+    }
+    assert!(s.crossings(-10.0).next().is_none());
+    assert_eq!(s.crossings(0.0).count(), 1);
+
+    // Starting at the end, it stops when it comes back.
+    s.set(0.0, 500.0, 0.0);
+    let back = s.crossings(0.0).nth(1).unwrap();
+    assert!(s.x(back * 0.5) > 10.0);
+    assert!(s.is_done(back + 0.001));
+}
+
+#[test]
+fn test_max_bounces() {
+    for bounces in 0..4 {
+        let mut s: Spring<f64> = Spring::with_constants(1.0, 90.0, 4.0);
+        s.set_limit(SpringLimit::MaxBounces(bounces));
+        s.snap(100.0);
+        s.set(0.0, 0.0, 0.0);
+        // Count the turning points by sampling, and check the motion is smooth.
+        let mut turns = 0;
+        let step = 0.001;
+        for i in 2..10000 {
+            let (a, b) = ((i - 1) as f64 * step, i as f64 * step);
+            if (s.dx(a) < 0.0) != (s.dx(b) < 0.0) && s.dx(b).abs() > 0.001 {
+                turns += 1;
+            }
+            assert!((s.x(b) - s.x(a)).abs() < 2.0);
+            assert!((s.dx(b) - s.dx(a)).abs() < 10.0);
+        }
+        assert_eq!(turns, bounces);
+        let t = s.settle_time();
+        assert!(s.is_done(t));
+        assert!(!s.is_done(t - 0.1));
+        // Crossings carry on into the critically damped tail.
+        let crossings: Vec<f64> = s.crossings(0.05).collect();
+        assert!(!crossings.is_empty());
+        for c in &crossings {
+            assert!((s.x(*c) - 0.05).abs() < 0.001);
+        }
+    }
+    // The limit doesn't change a spring that doesn't bounce.
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 100.0, 40.0);
+    let mut limited = s;
+    limited.set_limit(SpringLimit::MaxBounces(0));
+    s.set(50.0, 0.0, 0.0);
+    limited.set(50.0, 0.0, 0.0);
+    assert_eq!(s.x(0.1), limited.x(0.1));
+}
+
+#[test]
+fn test_hanging_spring() {
+    // Underdamped, critically damped and overdamped, hanging under gravity.
+    for &(k, c) in &[(90.0, 4.0), (100.0, 20.0), (100.0, 40.0)] {
+        let (m, g): (f64, f64) = (2.0, 980.0);
+        let mut s: HangingSpring<f64> = HangingSpring::with_gravity(m, k * m, c * m, g);
+        s.snap(0.0);
+        assert!((s.sag() - g / k).abs() < 1e-9);
+        assert_eq!(s.x(1.0), s.equilibrium());
+        // Drop it from the end point, and check the equation of motion with the extra force.
+        s.resume(0.0, 0.0);
+        for i in 0..20 {
+            let t = i as f64 * 0.05;
+            let a = (m * g - c * m * s.dx(t) - k * m * (s.x(t) - s.end())) / m;
+            assert!((s.ddx(t) - a).abs() < 1e-6);
+        }
+        assert_eq!(s.final_position(), s.equilibrium());
+        assert!(s.is_done(s.settle_time()));
+        // Underdamped springs fall past the equilibrium.
+        let below = s.time_for_position(s.equilibrium() + 1.0);
+        assert_eq!(below.is_finite(), c < 20.0);
+    }
+    // Turning the force off pulls it back up to the end point.
+    let mut s: HangingSpring<f64> = HangingSpring::new(1.0, 100.0, 20.0, 500.0);
+    s.snap(0.0);
+    s.set_force(0.0, 1.0);
+    assert_eq!(s.x(1.0), 5.0);
+    assert!(s.x(1.1) < 5.0);
+    assert_eq!(s.final_position(), 0.0);
+}
+
+#[test]
+fn test_extrema() {
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 90.0, 4.0);
+    s.snap(100.0);
+    s.set(0.0, 0.0, 0.0);
+    let extrema: Vec<(f64, f64)> = s.extrema().collect();
+    let zeros: Vec<(f64, f64)> = s.zero_crossings().collect();
+    assert!(extrema.len() > 10);
+    let half_period = s.period() / 2.0;
+    let ratio = (s.decay_rate() * half_period).exp();
+    for (i, &(t, x)) in extrema.iter().enumerate() {
+        assert!(s.dx(t).abs() < 1e-6);
+        assert_eq!(s.x(t), x);
+        assert!(x.abs() <= s.envelope(t));
+        assert_eq!(x < 0.0, i % 2 == 0);
+        if i > 0 {
+            let (last_t, last_x) = extrema[i - 1];
+            assert!((t - last_t - half_period).abs() < 1e-9);
+            assert!((x / last_x + ratio).abs() < 1e-9);
+        }
+    }
+    // Zero crossings come between the extrema, and agree with searching for them.
+    for (i, &(t, x)) in zeros.iter().take(extrema.len()).enumerate() {
+        assert!(x.abs() < 1e-9);
+        assert!(t < extrema[i].0 && (i == 0 || t > extrema[i - 1].0));
+        assert!((s.crossings(0.0).nth(i).unwrap() - t).abs() < 1e-5);
+    }
+    // The last extremum is about the size of the tolerance.
+    assert!(extrema.last().unwrap().1.abs() < 0.01);
+
+    // A clamped spring stops at the first zero crossing.
+    s.set_limit(SpringLimit::ClampOvershoot);
+    assert_eq!(s.extrema().count(), 0);
+    assert_eq!(s.zero_crossings().count(), 1);
+
+    // An overdamped spring thrown past its end turns around once and doesn't come back through it.
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 100.0, 40.0);
+    s.snap(0.0);
+    s.set(0.0, 200.0, 0.0);
+    let extrema: Vec<(f64, f64)> = s.extrema().collect();
+    assert_eq!(extrema.len(), 1);
+    assert!(extrema[0].1 > 0.0);
+    assert_eq!(s.zero_crossings().count(), 0);
+    assert!(s.period().is_infinite());
+    assert!(s.decay_rate() < 0.0);
+}
+
+#[test]
+fn test_extrema_max_bounces() {
+    for bounces in 0..5 {
+        let mut s: Spring<f64> = Spring::with_constants(1.0, 200.0, 2.0);
+        s.set_limit(SpringLimit::MaxBounces(bounces));
+        s.snap(100.0);
+        s.set(0.0, 0.0, 0.0);
+        // Each turnaround before the spring is cut off is reported once, and the critically damped tail
+        // which follows doesn't turn around or cross the end again.
+        let extrema: Vec<(f64, f64)> = s.extrema().collect();
+        let zeros: Vec<(f64, f64)> = s.zero_crossings().collect();
+        assert_eq!(extrema.len(), bounces);
+        assert_eq!(zeros.len(), bounces);
+        for events in &[&extrema, &zeros] {
+            for pair in events.windows(2) {
+                assert!(pair[1].0 > pair[0].0);
+            }
+        }
+        for (i, &(t, x)) in extrema.iter().enumerate() {
+            assert!(s.dx(t).abs() < 1e-6);
+            assert_eq!(x < 0.0, i % 2 == 0);
+            assert!(zeros[i].0 < t);
+        }
+    }
+    // Thrown from its end, the spring turns around where it's thrown to as well.
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 200.0, 2.0);
+    s.set_limit(SpringLimit::MaxBounces(2));
+    s.snap(0.0);
+    s.set(0.0, 1000.0, 0.0);
+    let extrema: Vec<(f64, f64)> = s.extrema().collect();
+    assert_eq!(extrema.len(), 2);
+    assert!(extrema[0].1 > 0.0 && extrema[1].1 < 0.0 && extrema[1].0 > extrema[0].0);
+    assert_eq!(s.zero_crossings().count(), 1);
+}
+
+#[test]
+fn test_crossings_set_later() {
+    // A spring set at two seconds, starting from the position that's searched for.
+    let mut s: Spring<f64> = Spring::with_constants(1.0, 200.0, 5.0);
+    s.snap(0.0);
+    s.set(0.0, 100.0, 2.0);
+    assert_eq!(s.time_for_position(0.0), 2.0);
+    let mut previous = 2.0;
+    for (i, t) in s.crossings(0.0).enumerate().take(4) {
+        assert!((s.x(t)).abs() < 1e-3);
+        assert!(i == 0 || t > previous + 0.1);
+        previous = t;
+    }
+
+    // Clamping the overshoot stops at the first return to the end, rather than at the start.
+    s.set_limit(SpringLimit::ClampOvershoot);
+    let back = s.crossings(0.0).nth(1).unwrap();
+    assert!(back > 2.1);
+    for i in 0..300 {
+        let t = 2.0 + i as f64 * 0.01;
+        assert!(s.x(t) > -1e-3);
+    }
+    assert_eq!(s.x(back + 0.01), 0.0);
+}